@@ -0,0 +1,394 @@
+// Additive n-of-n secret sharing, where the secret is the sum (XOR) of all the pieces, and
+// conversions to and from Shamir's secret sharing without reconstructing the secret.
+// Shamir to additive: each party of a `k`-subset weighs its share with its Lagrange coefficient at
+// `x = 0`, since the secret is the sum of the weighted shares.
+// Additive to Shamir: each party re-deals its piece with `dealer_rng`, and each holder sums the
+// sub-shares it receives, since the sum of sharings is a sharing of the sum.
+
+use alloc::vec::Vec;
+use hashbrown::HashSet;
+
+#[cfg(feature = "zeroize_memory")]
+use zeroize::Zeroize;
+
+use super::field::GF256;
+use super::math;
+use super::share::{new_share, share_x, Share};
+use super::SecretSharing;
+
+/// A piece of an additive sharing. The secret is the sum of all the pieces.
+///
+/// Can be serialized to and from a byte array, with the same length as the secret.
+#[derive(Clone)]
+#[cfg_attr(feature = "zeroize_memory", derive(Zeroize))]
+#[cfg_attr(feature = "zeroize_memory", zeroize(drop))]
+pub struct AdditiveShare<const POLY: u16> {
+    /// The piece of the secret.
+    pub y: Vec<GF256<POLY>>,
+}
+
+impl<const POLY: u16> From<&AdditiveShare<POLY>> for Vec<u8> {
+    fn from(s: &AdditiveShare<POLY>) -> Vec<u8> {
+        s.y.iter().map(|y| y.0).collect()
+    }
+}
+
+impl<const POLY: u16> From<&[u8]> for AdditiveShare<POLY> {
+    fn from(s: &[u8]) -> AdditiveShare<POLY> {
+        AdditiveShare {
+            y: s.iter().map(|y| GF256(*y)).collect(),
+        }
+    }
+}
+
+/// Struct which implements methods to generate pieces and recover secrets with an `n`-of-`n`
+/// additive sharing over a 256 bits Galois Field, where the tuple parameter is `n`.
+///
+/// Any `n - 1` pieces reveal nothing about the secret, and all `n` are required to recover it.
+///
+/// Usage example:
+/// ```
+/// # use ssskit::{ AdditiveSharing, AdditiveShare };
+/// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+/// # const POLY: u16 = 0x11d_u16;
+/// let additive = AdditiveSharing::<POLY>(3);
+/// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+/// let pieces = additive.dealer_rng(&[1, 2, 3], &mut rng).unwrap();
+/// assert_eq!(additive.recover(&pieces).unwrap(), vec![1, 2, 3]);
+/// assert!(additive.recover(&pieces[1..]).is_err());
+/// ```
+pub struct AdditiveSharing<const POLY: u16>(pub u8);
+
+impl<const POLY: u16> AdditiveSharing<POLY> {
+    /// Given a `secret` byte slice, returns the `n` pieces of a random additive sharing of it.
+    /// A random number generator has to be provided.
+    pub fn dealer_rng<R: rand::Rng>(
+        &self,
+        secret: &[u8],
+        rng: &mut R,
+    ) -> Result<Vec<AdditiveShare<POLY>>, &'static str> {
+        if self.0 == 0 {
+            return Err("At least 1 piece is required");
+        }
+
+        let mut random = alloc::vec![0u8; secret.len()];
+        let mut last: Vec<GF256<POLY>> = secret.iter().map(|s| GF256(*s)).collect();
+        let mut pieces = Vec::with_capacity(self.0 as usize);
+        for _ in 1..self.0 {
+            rng.fill(random.as_mut_slice());
+            let piece = AdditiveShare::from(random.as_slice());
+            for (l, y) in last.iter_mut().zip(piece.y.iter()) {
+                *l = l.clone() - y.clone();
+            }
+            pieces.push(piece);
+        }
+        pieces.push(AdditiveShare { y: last });
+
+        #[cfg(feature = "zeroize_memory")]
+        random.zeroize();
+
+        Ok(pieces)
+    }
+
+    /// Same as `dealer_rng`, using `rand::thread_rng`.
+    #[cfg(feature = "std")]
+    pub fn dealer(&self, secret: &[u8]) -> Result<Vec<AdditiveShare<POLY>>, &'static str> {
+        let mut rng = rand::thread_rng();
+        self.dealer_rng(secret, &mut rng)
+    }
+
+    /// Given all the `n` pieces of an additive sharing, recovers the secret.
+    /// If there are not exactly `n` pieces or their lengths differ, an `Err` is returned.
+    pub fn recover<'a, T>(&self, pieces: T) -> Result<Vec<u8>, &'static str>
+    where
+        T: IntoIterator<Item = &'a AdditiveShare<POLY>>,
+        T::IntoIter: Iterator<Item = &'a AdditiveShare<POLY>>,
+    {
+        let pieces: Vec<&AdditiveShare<POLY>> = pieces.into_iter().collect();
+        if pieces.is_empty() || pieces.len() != self.0 as usize {
+            return Err("All the pieces are required to recover the secret");
+        }
+        sum(&pieces)
+    }
+}
+
+/// Sums the pieces, which must have the same length.
+fn sum<const POLY: u16>(pieces: &[&AdditiveShare<POLY>]) -> Result<Vec<u8>, &'static str> {
+    if pieces.iter().any(|p| p.y.len() != pieces[0].y.len()) {
+        return Err("All pieces must have the same length");
+    }
+    Ok((0..pieces[0].y.len())
+        .map(|i| pieces.iter().map(|p| p.y[i].clone()).sum::<GF256<POLY>>().0)
+        .collect())
+}
+
+impl<const POLY: u16> SecretSharing<POLY> {
+    /// Converts the Shamir share of the party at `x` into its piece of a `k`-of-`k` additive
+    /// sharing among the `k` distinct parties at `subset`, which must include `x`.
+    pub fn additive_share(
+        &self,
+        x: u8,
+        share: &Share<POLY>,
+        subset: &[u8],
+    ) -> Result<AdditiveShare<POLY>, &'static str> {
+        let mut distinct = HashSet::new();
+        if subset.len() != self.0 as usize || !subset.iter().all(|x| distinct.insert(*x)) {
+            return Err("Exactly k distinct parties are required");
+        }
+        if subset.contains(&0) {
+            return Err("Parties x coordinates must not be 0");
+        }
+        let index = subset
+            .iter()
+            .position(|s| *s == x)
+            .ok_or("The party must be one of the subset")?;
+
+        let x_samples: Vec<GF256<POLY>> = subset.iter().map(|s| GF256(*s)).collect();
+        let weight = math::lagrange_coefficients(&x_samples, GF256(0)).swap_remove(index);
+        Ok(AdditiveShare {
+            y: share.y.iter().map(|y| weight.clone() * y.clone()).collect(),
+        })
+    }
+
+    /// Converts the first `k` known shares into the pieces of a `k`-of-`k` additive sharing, as
+    /// each of their holders would with `additive_share`. Shares are passed as in `recover`.
+    ///
+    /// Example:
+    /// ```
+    /// # use ssskit::{ AdditiveSharing, SecretSharing, Share };
+    /// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    /// # const POLY: u16 = 0x11d_u16;
+    /// # let sss = SecretSharing::<POLY>(3);
+    /// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// let shares = sss
+    ///     .dealer_rng(&[1, 2, 3], &mut rng)
+    ///     .take(5)
+    ///     .map(Some)
+    ///     .collect::<Vec<Option<Share<POLY>>>>();
+    /// let pieces = sss.additive_shares(&shares).unwrap();
+    /// assert_eq!(AdditiveSharing::<POLY>(3).recover(&pieces).unwrap(), vec![1, 2, 3]);
+    /// // And back to a 2-of-4 Shamir sharing
+    /// let shares = SecretSharing::<POLY>(2).convert_additive_rng(&pieces, 4, &mut rng).unwrap();
+    /// let shares = shares.into_iter().map(Some).collect::<Vec<_>>();
+    /// assert_eq!(SecretSharing::<POLY>(2).recover(&shares).unwrap(), vec![1, 2, 3]);
+    /// ```
+    pub fn additive_shares<'a, T>(
+        &self,
+        shares: T,
+    ) -> Result<Vec<AdditiveShare<POLY>>, &'static str>
+    where
+        T: IntoIterator<Item = &'a Option<Share<POLY>>>,
+        T::IntoIter: Iterator<Item = &'a Option<Share<POLY>>>,
+    {
+        let shares: Vec<&Option<Share<POLY>>> = shares.into_iter().collect();
+        let (positions, values) = self.collect_positioned(shares.iter().copied())?;
+        let subset: Vec<u8> = values.iter().take(self.0 as usize).map(|s| s.x.0).collect();
+
+        positions
+            .iter()
+            .zip(subset.iter())
+            .map(|(&i, &x)| self.additive_share(x, shares[i].as_ref().unwrap(), &subset))
+            .collect()
+    }
+
+    /// First round of a conversion to Shamir's secret sharing, run by the holder of `piece`:
+    /// re-deals it with this threshold to the `n` holders at `x = 1..=n`.
+    /// A random number generator has to be provided.
+    pub fn reshare_additive_rng<R: rand::Rng>(
+        &self,
+        piece: &AdditiveShare<POLY>,
+        n: u8,
+        rng: &mut R,
+    ) -> Result<Vec<Share<POLY>>, &'static str> {
+        if self.0 == 0 || n < self.0 {
+            return Err("A threshold of at least 1 and at most n is required");
+        }
+        let bytes = Vec::from(piece);
+        let shares = self.dealer_rng(&bytes, rng).take(n as usize).collect();
+
+        #[cfg(feature = "zeroize_memory")]
+        {
+            let mut bytes = bytes;
+            bytes.zeroize();
+        }
+
+        Ok(shares)
+    }
+
+    /// Same as `reshare_additive_rng`, using `rand::thread_rng`.
+    #[cfg(feature = "std")]
+    pub fn reshare_additive(
+        &self,
+        piece: &AdditiveShare<POLY>,
+        n: u8,
+    ) -> Result<Vec<Share<POLY>>, &'static str> {
+        let mut rng = rand::thread_rng();
+        self.reshare_additive_rng(piece, n, &mut rng)
+    }
+
+    /// Second round of a conversion to Shamir's secret sharing, run by the holder at `x`: sums the
+    /// sub-shares at `x` received from every piece holder into its share.
+    /// Without `share_x`, the sub-shares and the new share are at position `x - 1`.
+    pub fn combine_additive<'a, T>(&self, x: u8, sub_shares: T) -> Result<Share<POLY>, &'static str>
+    where
+        T: IntoIterator<Item = &'a Share<POLY>>,
+        T::IntoIter: Iterator<Item = &'a Share<POLY>>,
+    {
+        let sub_shares: Vec<&Share<POLY>> = sub_shares.into_iter().collect();
+        if x == 0 || sub_shares.is_empty() {
+            return Err("At least one sub-share at a non-zero x coordinate is required");
+        }
+        if sub_shares
+            .iter()
+            .any(|s| share_x(x as usize - 1, s) != x || s.y.len() != sub_shares[0].y.len())
+        {
+            return Err(
+                "All sub-shares must be at the holder x coordinate and have the same length",
+            );
+        }
+
+        let y = (0..sub_shares[0].y.len())
+            .map(|i| sub_shares.iter().map(|s| s.y[i].clone()).sum())
+            .collect();
+        Ok(new_share(GF256(x), y))
+    }
+
+    /// Converts additive pieces into a sharing with this threshold among `n` holders at
+    /// `x = 1..=n`, as each piece holder and new holder would in the protocol.
+    /// A random number generator has to be provided.
+    pub fn convert_additive_rng<'a, T, R: rand::Rng>(
+        &self,
+        pieces: T,
+        n: u8,
+        rng: &mut R,
+    ) -> Result<Vec<Share<POLY>>, &'static str>
+    where
+        T: IntoIterator<Item = &'a AdditiveShare<POLY>>,
+        T::IntoIter: Iterator<Item = &'a AdditiveShare<POLY>>,
+    {
+        let dealt = pieces
+            .into_iter()
+            .map(|piece| self.reshare_additive_rng(piece, n, rng))
+            .collect::<Result<Vec<_>, _>>()?;
+        (1..=n)
+            .map(|x| self.combine_additive(x, dealt.iter().map(|d| &d[x as usize - 1])))
+            .collect()
+    }
+
+    /// Same as `convert_additive_rng`, using `rand::thread_rng`.
+    #[cfg(feature = "std")]
+    pub fn convert_additive<'a, T>(
+        &self,
+        pieces: T,
+        n: u8,
+    ) -> Result<Vec<Share<POLY>>, &'static str>
+    where
+        T: IntoIterator<Item = &'a AdditiveShare<POLY>>,
+        T::IntoIter: Iterator<Item = &'a AdditiveShare<POLY>>,
+    {
+        let mut rng = rand::thread_rng();
+        self.convert_additive_rng(pieces, n, &mut rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AdditiveShare, AdditiveSharing};
+    use crate::{SecretSharing, Share};
+    use alloc::vec::Vec;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    use rstest::rstest;
+
+    const POLY: u16 = 0x11d_u16;
+
+    #[rstest]
+    #[case(1)]
+    #[case(2)]
+    #[case(7)]
+    #[case(255)]
+    fn additive_roundtrip(#[case] n: u8) {
+        let additive = AdditiveSharing::<POLY>(n);
+        let mut rng = ChaCha8Rng::from_seed([0x45; 32]);
+        let secret = [3, 1, 4, 1, 5];
+        let pieces = additive.dealer_rng(&secret, &mut rng).unwrap();
+        assert_eq!(pieces.len(), n as usize);
+        assert_eq!(additive.recover(&pieces).unwrap(), secret);
+
+        let pieces: Vec<AdditiveShare<POLY>> = pieces
+            .iter()
+            .map(|p| AdditiveShare::from(Vec::from(p).as_slice()))
+            .collect();
+        assert_eq!(additive.recover(&pieces).unwrap(), secret);
+        if n > 1 {
+            assert!(additive.recover(&pieces[1..]).is_err());
+        }
+    }
+
+    #[rstest]
+    #[case(1, 3, 2, 2)]
+    #[case(2, 5, 3, 6)]
+    #[case(4, 4, 1, 1)]
+    #[case(10, 30, 20, 40)]
+    fn shamir_to_additive_and_back(
+        #[case] k: u8,
+        #[case] n: usize,
+        #[case] new_k: u8,
+        #[case] new_n: u8,
+    ) {
+        let sss = SecretSharing::<POLY>(k);
+        let mut rng = ChaCha8Rng::from_seed([0x45; 32]);
+        let secret = [9, 8, 7, 6];
+        let mut shares: Vec<Option<Share<POLY>>> = sss
+            .dealer_rng(&secret, &mut rng)
+            .take(n)
+            .map(Some)
+            .collect();
+        for share in shares.iter_mut().take(n - k as usize) {
+            *share = None;
+        }
+
+        let pieces = sss.additive_shares(&shares).unwrap();
+        assert_eq!(AdditiveSharing::<POLY>(k).recover(&pieces).unwrap(), secret);
+
+        let new = SecretSharing::<POLY>(new_k);
+        let mut new_shares: Vec<Option<Share<POLY>>> = new
+            .convert_additive_rng(&pieces, new_n, &mut rng)
+            .unwrap()
+            .into_iter()
+            .map(Some)
+            .collect();
+        assert_eq!(new.recover(&new_shares).unwrap(), secret);
+        for share in new_shares.iter_mut().take((new_n - new_k) as usize) {
+            *share = None;
+        }
+        assert_eq!(new.recover(&new_shares).unwrap(), secret);
+    }
+
+    #[test]
+    fn invalid_parameters_err() {
+        let sss = SecretSharing::<POLY>(2);
+        let mut rng = ChaCha8Rng::from_seed([0x45; 32]);
+        let shares: Vec<Share<POLY>> = sss.dealer_rng(&[1, 2], &mut rng).take(3).collect();
+
+        assert!(sss.additive_share(1, &shares[0], &[1, 2, 3]).is_err());
+        assert!(sss.additive_share(1, &shares[0], &[2, 2]).is_err());
+        assert!(sss.additive_share(1, &shares[0], &[2, 3]).is_err());
+        assert!(sss.additive_share(0, &shares[0], &[0, 3]).is_err());
+
+        // Sub-shares at other x coordinates
+        #[cfg(feature = "share_x")]
+        assert!(sss.combine_additive(1, &shares).is_err());
+        assert!(sss.combine_additive(0, &shares[..1]).is_err());
+        assert!(sss.combine_additive(1, &shares[..1]).is_ok());
+
+        let piece = AdditiveShare::from(&[1u8, 2][..]);
+        assert!(sss.reshare_additive_rng(&piece, 1, &mut rng).is_err());
+        assert!(AdditiveSharing::<POLY>(0)
+            .dealer_rng(&[1], &mut rng)
+            .is_err());
+        let short = AdditiveShare::from(&[1u8][..]);
+        assert!(AdditiveSharing::<POLY>(2)
+            .recover([&piece, &short])
+            .is_err());
+    }
+}
@@ -0,0 +1,575 @@
+//! `ssskit` command-line tool to split, combine, reshare and inspect secret shares.
+//!
+//! Share files contain the share bytes in the crate serialization format, optionally encoded as
+//! hex or base64: `[x, y...]` with `--share-x`, or only the `y` bytes otherwise, in which case the
+//! `x` coordinate is given as `X=PATH` or implied by the position of the share in the arguments.
+
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use base64::Engine;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use ssskit::{SecretSharing, Share, PRIMITIVE_POLYS};
+use zeroize::{Zeroize, Zeroizing};
+
+#[derive(Parser)]
+#[command(
+    name = "ssskit",
+    version,
+    about = "Shamir's Secret Sharing over GF(256)"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Split a secret read from a file or stdin into share files
+    Split {
+        #[command(flatten)]
+        scheme: Scheme,
+        #[command(flatten)]
+        output: Output,
+        /// Number of shares to generate
+        #[arg(short = 'n', long)]
+        shares: u8,
+        /// File to read the secret from, stdin if omitted or `-`
+        input: Option<PathBuf>,
+    },
+    /// Recover a secret from share files
+    Combine {
+        #[command(flatten)]
+        scheme: Scheme,
+        /// File to write the secret to, stdout if omitted or `-`
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Share files, as `PATH` or `X=PATH`
+        #[arg(required = true)]
+        shares: Vec<String>,
+    },
+    /// Regenerate all `n` shares from at least threshold share files
+    Reshare {
+        #[command(flatten)]
+        scheme: Scheme,
+        #[command(flatten)]
+        output: Output,
+        /// Number of shares to regenerate
+        #[arg(short = 'n', long)]
+        shares: u8,
+        /// Share files, as `PATH` or `X=PATH`
+        #[arg(required = true)]
+        inputs: Vec<String>,
+    },
+    /// Decode a share and print its metadata
+    Inspect {
+        #[command(flatten)]
+        layout: Layout,
+        /// Share file, as `PATH` or `X=PATH`, stdin if omitted or `-`
+        share: Option<String>,
+    },
+}
+
+#[derive(Args)]
+struct Layout {
+    /// Share files carry the x coordinate as their first byte
+    #[arg(long)]
+    share_x: bool,
+    /// Encoding of the share files
+    #[arg(short, long, value_enum, default_value_t = Encoding::Hex)]
+    encoding: Encoding,
+}
+
+#[derive(Args)]
+struct Scheme {
+    /// Minimum number of shares required to recover the secret
+    #[arg(short = 'k', long)]
+    threshold: u8,
+    /// Irreducible polynomial of the field, e.g. 0x11d
+    #[arg(short, long, default_value = "0x11d", value_parser = parse_poly)]
+    poly: u16,
+    #[command(flatten)]
+    layout: Layout,
+}
+
+#[derive(Args)]
+struct Output {
+    /// Directory to write the share files to
+    #[arg(short = 'd', long, default_value = ".")]
+    out_dir: PathBuf,
+    /// Share file names are `<PREFIX>-<X>.<EXTENSION>`
+    #[arg(long, default_value = "share")]
+    prefix: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum Encoding {
+    Raw,
+    Hex,
+    Base64,
+}
+
+impl Encoding {
+    fn extension(self) -> &'static str {
+        match self {
+            Encoding::Raw => "bin",
+            Encoding::Hex => "hex",
+            Encoding::Base64 => "b64",
+        }
+    }
+
+    fn encode(self, bytes: &[u8]) -> Vec<u8> {
+        match self {
+            Encoding::Raw => bytes.to_vec(),
+            Encoding::Hex => hex::encode(bytes).into_bytes(),
+            Encoding::Base64 => base64::engine::general_purpose::STANDARD
+                .encode(bytes)
+                .into_bytes(),
+        }
+    }
+
+    fn decode(self, data: &[u8]) -> Result<Vec<u8>, String> {
+        let text = || {
+            std::str::from_utf8(data)
+                .map(str::trim)
+                .map_err(|_| "share is not valid text".to_string())
+        };
+        match self {
+            Encoding::Raw => Ok(data.to_vec()),
+            Encoding::Hex => hex::decode(text()?).map_err(|e| format!("invalid hex share: {e}")),
+            Encoding::Base64 => base64::engine::general_purpose::STANDARD
+                .decode(text()?)
+                .map_err(|e| format!("invalid base64 share: {e}")),
+        }
+    }
+}
+
+fn parse_poly(s: &str) -> Result<u16, String> {
+    let poly = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(h) => u16::from_str_radix(h, 16),
+        None => s.parse(),
+    }
+    .map_err(|e| format!("invalid polynomial: {e}"))?;
+
+    if PRIMITIVE_POLYS.contains(&poly) {
+        Ok(poly)
+    } else {
+        Err(format!("{poly:#x} is not a supported primitive polynomial"))
+    }
+}
+
+/// A decoded share file: its `x` coordinate and `y` bytes.
+struct ShareFile {
+    x: u8,
+    y: Zeroizing<Vec<u8>>,
+}
+
+/// Splits a `[X=]PATH` argument into its optional x coordinate and path.
+fn parse_share_arg(arg: &str) -> Result<(Option<u8>, &str), String> {
+    match arg.split_once('=') {
+        Some((x, path)) if !x.is_empty() && x.bytes().all(|b| b.is_ascii_digit()) => {
+            match x.parse::<u8>() {
+                Ok(x) if x > 0 => Ok((Some(x), path)),
+                _ => Err(format!("invalid x coordinate `{x}`, expected 1 to 255")),
+            }
+        }
+        _ => Ok((None, arg)),
+    }
+}
+
+fn read_input(path: Option<&Path>) -> Result<Zeroizing<Vec<u8>>, String> {
+    let mut data = Zeroizing::new(Vec::new());
+    match path {
+        Some(path) if path != Path::new("-") => {
+            let mut file = fs::File::open(path).map_err(|e| format!("{}: {e}", path.display()))?;
+            file.read_to_end(&mut data)
+                .map_err(|e| format!("{}: {e}", path.display()))?;
+        }
+        _ => {
+            io::stdin()
+                .read_to_end(&mut data)
+                .map_err(|e| format!("stdin: {e}"))?;
+        }
+    }
+    Ok(data)
+}
+
+/// Reads and decodes a share file. Without `--share-x` and without an explicit `X=`,
+/// `position` (1-based) is used as the x coordinate.
+fn read_share(arg: &str, position: usize, layout: &Layout) -> Result<ShareFile, String> {
+    let (x, path) = parse_share_arg(arg)?;
+    let data = read_input(Some(Path::new(path)))?;
+    let mut bytes = Zeroizing::new(
+        layout
+            .encoding
+            .decode(&data)
+            .map_err(|e| format!("{path}: {e}"))?,
+    );
+
+    let x = if layout.share_x {
+        if bytes.is_empty() {
+            return Err(format!("{path}: share is empty"));
+        }
+        let embedded = bytes.remove(0);
+        match x {
+            Some(x) if x != embedded => {
+                return Err(format!("{path}: share has x = {embedded}, not {x}"))
+            }
+            _ => embedded,
+        }
+    } else {
+        match x {
+            Some(x) => x,
+            None => u8::try_from(position)
+                .map_err(|_| "at most 255 shares can be combined".to_string())?,
+        }
+    };
+
+    if x == 0 {
+        return Err(format!("{path}: share has x = 0"));
+    }
+    Ok(ShareFile { x, y: bytes })
+}
+
+fn read_shares(args: &[String], layout: &Layout) -> Result<Vec<ShareFile>, String> {
+    args.iter()
+        .enumerate()
+        .map(|(i, arg)| read_share(arg, i + 1, layout))
+        .collect()
+}
+
+/// Converts a share file into the crate `Share`, whichever share variant the crate was built with.
+fn to_share<const POLY: u16>(share: &ShareFile) -> Result<Share<POLY>, String> {
+    #[cfg(feature = "share_x")]
+    let bytes = Zeroizing::new([&[share.x], share.y.as_slice()].concat());
+    #[cfg(not(feature = "share_x"))]
+    let bytes = Zeroizing::new(share.y.to_vec());
+    Share::<POLY>::try_from(bytes.as_slice()).map_err(|e| format!("share x = {}: {e}", share.x))
+}
+
+/// Places the shares at positions `x - 1`, as expected by `recover` and `recover_shares`.
+fn place<const POLY: u16>(
+    shares: &[ShareFile],
+    n: usize,
+) -> Result<Vec<Option<Share<POLY>>>, String> {
+    let mut placed: Vec<Option<Share<POLY>>> = vec![None; n];
+    for share in shares {
+        let slot = placed
+            .get_mut(share.x as usize - 1)
+            .ok_or_else(|| format!("share x = {} is out of range 1..={n}", share.x))?;
+        if slot.is_some() {
+            return Err(format!("duplicate share x = {}", share.x));
+        }
+        *slot = Some(to_share(share)?);
+    }
+    Ok(placed)
+}
+
+fn write_share(output: &Output, layout: &Layout, x: u8, y: &[u8]) -> Result<PathBuf, String> {
+    let mut bytes = Zeroizing::new(Vec::with_capacity(y.len() + 1));
+    if layout.share_x {
+        bytes.push(x);
+    }
+    bytes.extend_from_slice(y);
+
+    let path = output.out_dir.join(format!(
+        "{}-{}.{}",
+        output.prefix,
+        x,
+        layout.encoding.extension()
+    ));
+    let encoded = Zeroizing::new(layout.encoding.encode(&bytes));
+    write_private(&path, encoded.as_slice())?;
+    Ok(path)
+}
+
+/// Writes `bytes` to `path`, readable and writable only by the owner on unix.
+fn write_private(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let error = |e: io::Error| format!("{}: {e}", path.display());
+    let mut file = options.open(path).map_err(error)?;
+    // The mode only applies to new files
+    #[cfg(unix)]
+    file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))
+        .map_err(error)?;
+    file.write_all(bytes).map_err(error)
+}
+
+fn y_bytes<const POLY: u16>(share: &Share<POLY>) -> Zeroizing<Vec<u8>> {
+    Zeroizing::new(share.y.iter().map(|p| p.0).collect())
+}
+
+fn split<const POLY: u16>(
+    scheme: &Scheme,
+    output: &Output,
+    n: u8,
+    input: Option<&Path>,
+) -> Result<(), String> {
+    if scheme.threshold == 0 || n < scheme.threshold {
+        return Err(
+            "the number of shares must be at least the threshold, which must be positive".into(),
+        );
+    }
+
+    let secret = read_input(input)?;
+    if secret.is_empty() {
+        return Err("the secret is empty".into());
+    }
+
+    let sss = SecretSharing::<POLY>(scheme.threshold);
+    for (i, share) in sss.dealer(&secret).take(n as usize).enumerate() {
+        let path = write_share(output, &scheme.layout, i as u8 + 1, &y_bytes(&share))?;
+        eprintln!("wrote {}", path.display());
+    }
+    Ok(())
+}
+
+fn combine<const POLY: u16>(
+    scheme: &Scheme,
+    output: Option<&Path>,
+    args: &[String],
+) -> Result<(), String> {
+    let shares = read_shares(args, &scheme.layout)?;
+    let n = shares.iter().map(|s| s.x as usize).max().unwrap_or(0);
+    let sss = SecretSharing::<POLY>(scheme.threshold);
+    let mut secret = sss.recover(&place::<POLY>(&shares, n)?)?;
+
+    let written = match output {
+        Some(path) if path != Path::new("-") => write_private(path, &secret),
+        _ => {
+            let mut stdout = io::stdout().lock();
+            stdout
+                .write_all(&secret)
+                .and_then(|_| stdout.flush())
+                .map_err(|e| format!("stdout: {e}"))
+        }
+    };
+    secret.zeroize();
+    written
+}
+
+fn reshare<const POLY: u16>(
+    scheme: &Scheme,
+    output: &Output,
+    n: u8,
+    args: &[String],
+) -> Result<(), String> {
+    let shares = read_shares(args, &scheme.layout)?;
+    let placed = place::<POLY>(&shares, n as usize)?;
+    let sss = SecretSharing::<POLY>(scheme.threshold);
+    let recovered = sss.recover_shares(placed.iter().map(Option::as_ref), n as usize)?;
+
+    for (i, share) in recovered.iter().enumerate() {
+        let path = write_share(output, &scheme.layout, i as u8 + 1, &y_bytes(share))?;
+        eprintln!("wrote {}", path.display());
+    }
+    Ok(())
+}
+
+fn inspect(layout: &Layout, arg: Option<&str>) -> Result<String, String> {
+    let describe = |share: ShareFile, x_known: bool| {
+        let x = if x_known {
+            share.x.to_string()
+        } else {
+            "implied by position".to_string()
+        };
+        format!(
+            "layout: {}\nencoding: {:?}\nx: {}\nsecret length: {} bytes\n",
+            if layout.share_x { "share_x" } else { "no x" },
+            layout.encoding,
+            x,
+            share.y.len()
+        )
+    };
+
+    match arg {
+        Some(arg) if arg != "-" => parse_share_arg(arg).and_then(|(x, _)| {
+            read_share(arg, 1, layout).map(|s| describe(s, x.is_some() || layout.share_x))
+        }),
+        _ => read_input(None).and_then(|data| {
+            let mut y = Zeroizing::new(layout.encoding.decode(&data)?);
+            let x = if layout.share_x && !y.is_empty() {
+                y.remove(0)
+            } else {
+                0
+            };
+            Ok(describe(ShareFile { x, y }, layout.share_x))
+        }),
+    }
+}
+
+/// Calls `$f::<POLY>$args` with `POLY` being the value of `$poly`, which is only known at runtime.
+/// Every entry of `PRIMITIVE_POLYS` is instantiated, by its index.
+macro_rules! with_poly {
+    ($poly:expr, $f:ident $args:tt, [$($i:literal),*]) => {
+        match $poly {
+            $(p if p == PRIMITIVE_POLYS[$i] => $f::<{ PRIMITIVE_POLYS[$i] }> $args,)*
+            p => Err(format!("{p:#x} is not a supported primitive polynomial")),
+        }
+    };
+    ($poly:expr, $f:ident $args:tt) => {
+        with_poly!($poly, $f $args, [
+            0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23,
+            24, 25, 26, 27, 28, 29
+        ])
+    };
+}
+
+// `with_poly!` must list an index for every primitive polynomial
+const _: () = assert!(PRIMITIVE_POLYS.len() == 30);
+
+fn run(cli: Cli) -> Result<(), String> {
+    match cli.command {
+        Command::Split {
+            scheme,
+            output,
+            shares,
+            input,
+        } => with_poly!(
+            scheme.poly,
+            split(&scheme, &output, shares, input.as_deref())
+        ),
+        Command::Combine {
+            scheme,
+            output,
+            shares,
+        } => with_poly!(scheme.poly, combine(&scheme, output.as_deref(), &shares)),
+        Command::Reshare {
+            scheme,
+            output,
+            shares,
+            inputs,
+        } => with_poly!(scheme.poly, reshare(&scheme, &output, shares, &inputs)),
+        Command::Inspect { layout, share } => {
+            print!("{}", inspect(&layout, share.as_deref())?);
+            Ok(())
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ssskit-cli-{}-{name}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn parse_poly_works() {
+        assert_eq!(parse_poly("0x11d"), Ok(0x11d));
+        assert_eq!(parse_poly("283"), Ok(0x11b));
+        assert!(parse_poly("0x100").is_err());
+        assert!(parse_poly("foo").is_err());
+    }
+
+    #[test]
+    fn parse_share_arg_works() {
+        assert_eq!(parse_share_arg("3=a.hex"), Ok((Some(3), "a.hex")));
+        assert_eq!(parse_share_arg("a.hex"), Ok((None, "a.hex")));
+        assert_eq!(parse_share_arg("dir/x=1.hex"), Ok((None, "dir/x=1.hex")));
+        assert!(parse_share_arg("0=a.hex").is_err());
+        assert!(parse_share_arg("256=a.hex").is_err());
+    }
+
+    #[test]
+    fn encoding_roundtrip() {
+        let bytes = [0u8, 1, 254, 255];
+        for encoding in [Encoding::Raw, Encoding::Hex, Encoding::Base64] {
+            let mut encoded = encoding.encode(&bytes);
+            if encoding != Encoding::Raw {
+                encoded.push(b'\n');
+            }
+            assert_eq!(encoding.decode(&encoded).unwrap(), bytes);
+        }
+        assert!(Encoding::Hex.decode(b"zz").is_err());
+    }
+
+    #[test]
+    fn split_combine_reshare_works() {
+        for (share_x, encoding) in [(false, Encoding::Hex), (true, Encoding::Base64)] {
+            let dir = temp_dir(&format!("{share_x}-{encoding:?}"));
+            let secret_path = dir.join("secret");
+            fs::write(&secret_path, b"correct horse battery staple").unwrap();
+
+            let layout = || Layout { share_x, encoding };
+            let scheme = Scheme {
+                threshold: 3,
+                poly: 0x11b,
+                layout: layout(),
+            };
+            let output = Output {
+                out_dir: dir.clone(),
+                prefix: "share".into(),
+            };
+            with_poly!(
+                0x11b_u16,
+                split(&scheme, &output, 5, Some(secret_path.as_path()))
+            )
+            .unwrap();
+
+            let ext = encoding.extension();
+            let file = |x: u8| dir.join(format!("share-{x}.{ext}")).display().to_string();
+            let args = vec![
+                format!("2={}", file(2)),
+                format!("4={}", file(4)),
+                format!("5={}", file(5)),
+            ];
+            let recovered = dir.join("recovered");
+            with_poly!(
+                0x11b_u16,
+                combine(&scheme, Some(recovered.as_path()), &args)
+            )
+            .unwrap();
+            assert_eq!(
+                fs::read(&recovered).unwrap(),
+                b"correct horse battery staple"
+            );
+
+            fs::remove_file(file(1)).unwrap();
+            with_poly!(0x11b_u16, reshare(&scheme, &output, 5, &args)).unwrap();
+            let args = vec![file(1), file(2), file(3)];
+            with_poly!(
+                0x11b_u16,
+                combine(&scheme, Some(recovered.as_path()), &args)
+            )
+            .unwrap();
+            assert_eq!(
+                fs::read(&recovered).unwrap(),
+                b"correct horse battery staple"
+            );
+
+            let report = inspect(&layout(), Some(&file(3))).unwrap();
+            assert!(report.contains("secret length: 28 bytes"));
+            assert_eq!(report.contains("x: 3"), share_x);
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                let mode = fs::metadata(file(3)).unwrap().permissions().mode();
+                assert_eq!(mode & 0o777, 0o600);
+            }
+            let missing = dir.join("missing.hex").to_string_lossy().into_owned();
+            assert!(inspect(&layout(), Some(&missing)).is_err());
+
+            fs::remove_dir_all(&dir).unwrap();
+        }
+    }
+}
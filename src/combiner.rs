@@ -0,0 +1,197 @@
+// Incremental recovery, for shares collected one at a time. Each share is validated as soon as it
+// is added, and the Lagrange weights at 0 of the shares collected so far are kept up to date, so that
+// finalizing is a single weighted sum.
+
+use alloc::vec::Vec;
+
+#[cfg(feature = "zeroize_memory")]
+use zeroize::Zeroize;
+
+use super::field::GF256;
+use super::share::{share_x, Share, ShareWithX};
+use super::SecretSharing;
+
+/// A stateful recovery of a secret from shares added one at a time.
+///
+/// Usage example:
+/// ```
+/// # use ssskit::{ SecretSharing, Share };
+/// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+/// # const POLY: u16 = 0x11d_u16;
+/// let sss = SecretSharing::<POLY>(3);
+/// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+/// let shares = sss.dealer_rng(&[1, 2, 3], &mut rng).take(5).collect::<Vec<Share<POLY>>>();
+///
+/// let mut combiner = sss.combiner();
+/// assert_eq!(combiner.remaining(), 3);
+/// // Each share is added with its position in the dealt list
+/// assert_eq!(combiner.add(4, &shares[4]), Ok(2));
+/// assert!(combiner.add(4, &shares[4]).is_err());
+/// assert_eq!(combiner.add(0, &shares[0]), Ok(1));
+/// assert_eq!(combiner.add(2, &shares[2]), Ok(0));
+/// assert_eq!(combiner.finalize().unwrap(), vec![1, 2, 3]);
+/// ```
+#[cfg_attr(feature = "zeroize_memory", derive(Zeroize))]
+#[cfg_attr(feature = "zeroize_memory", zeroize(drop))]
+pub struct Combiner<const POLY: u16> {
+    threshold: u8,
+    shares: Vec<ShareWithX<POLY>>,
+    /// The Lagrange weight at 0 of each share among the ones added so far.
+    weights: Vec<GF256<POLY>>,
+}
+
+impl<const POLY: u16> Combiner<POLY> {
+    /// Returns an empty combiner which recovers a secret shared with threshold `k`.
+    pub fn new(k: u8) -> Self {
+        Combiner {
+            threshold: k,
+            shares: Vec::with_capacity(k as usize),
+            weights: Vec::with_capacity(k as usize),
+        }
+    }
+
+    /// Adds the share at `position` (0-based) in the list of dealt shares, and returns how many
+    /// more shares are needed. With `share_x`, the `x` coordinate of the share is used instead of
+    /// its position.
+    ///
+    /// Returns an `Err`, leaving the combiner unchanged, if the threshold was already reached, if
+    /// the share does not have the same length as the previous ones, or if a share at the same `x`
+    /// was already added.
+    pub fn add(&mut self, position: usize, share: &Share<POLY>) -> Result<usize, &'static str> {
+        if self.remaining() == 0 {
+            return Err("The threshold is already reached");
+        }
+        #[cfg(not(feature = "share_x"))]
+        if position >= 255 {
+            return Err("The position of a share must be lower than 255");
+        }
+        let x = GF256(share_x(position, share));
+        if x.0 == 0 {
+            return Err("A share cannot be at x = 0");
+        }
+        if self
+            .shares
+            .first()
+            .is_some_and(|first| first.y.len() != share.y.len())
+        {
+            return Err("All shares must have the same length");
+        }
+        if let Some(previous) = self.shares.iter().find(|s| s.x == x) {
+            return Err(if previous.y == share.y {
+                "The share was already added"
+            } else {
+                "Conflicting share at an x coordinate already added"
+            });
+        }
+
+        // Adding `x` scales the weight of each previous share at `x_i` by `(0 - x) / (x_i - x)`
+        let mut weight = GF256(1);
+        for (s, w) in self.shares.iter().zip(self.weights.iter_mut()) {
+            *w = w.clone() * (GF256(0) - x.clone()) / (s.x.clone() - x.clone());
+            weight = weight * (GF256(0) - s.x.clone()) / (x.clone() - s.x.clone());
+        }
+        self.weights.push(weight);
+        self.shares.push(ShareWithX {
+            x,
+            y: share.y.clone(),
+        });
+        Ok(self.remaining())
+    }
+
+    /// Returns how many more shares are needed to reach the threshold.
+    pub fn remaining(&self) -> usize {
+        (self.threshold as usize).saturating_sub(self.shares.len())
+    }
+
+    /// Recovers the secret from the shares added so far. Returns an `Err` if the threshold is not
+    /// reached yet.
+    pub fn finalize(&self) -> Result<Vec<u8>, &'static str> {
+        if self.shares.is_empty() || self.remaining() > 0 {
+            return Err("Not enough shares to recover original secret");
+        }
+        Ok((0..self.shares[0].y.len())
+            .map(|i| {
+                self.shares
+                    .iter()
+                    .zip(self.weights.iter())
+                    .map(|(s, w)| w.clone() * s.y[i].clone())
+                    .sum::<GF256<POLY>>()
+                    .0
+            })
+            .collect())
+    }
+}
+
+impl<const POLY: u16> SecretSharing<POLY> {
+    /// Returns an empty [`Combiner`] to recover the secret from shares added one at a time.
+    pub fn combiner(&self) -> Combiner<POLY> {
+        Combiner::new(self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{SecretSharing, Share};
+    use alloc::{vec, vec::Vec};
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    use rstest::rstest;
+
+    const POLY: u16 = 0x11d_u16;
+
+    fn deal(sss: &SecretSharing<POLY>, secret: &[u8], n: usize) -> Vec<Share<POLY>> {
+        let mut rng = ChaCha8Rng::from_seed([0x50; 32]);
+        sss.dealer_rng(secret, &mut rng).take(n).collect()
+    }
+
+    #[rstest]
+    #[case(1, 1, vec![0])]
+    #[case(2, 3, vec![2, 0])]
+    #[case(3, 5, vec![4, 1, 3])]
+    #[case(10, 255, vec![254, 200, 150, 99, 77, 50, 31, 12, 3, 0])]
+    fn recovers_in_any_order(#[case] k: u8, #[case] n: usize, #[case] positions: Vec<usize>) {
+        let sss = SecretSharing::<POLY>(k);
+        let secret = [0, 1, 0x53, 0xff];
+        let shares = deal(&sss, &secret, n);
+
+        let mut combiner = sss.combiner();
+        for (added, &position) in positions.iter().enumerate() {
+            assert_eq!(combiner.remaining(), k as usize - added);
+            assert!(combiner.finalize().is_err());
+            assert_eq!(
+                combiner.add(position, &shares[position]),
+                Ok(k as usize - added - 1)
+            );
+        }
+        assert_eq!(combiner.finalize().unwrap(), secret);
+    }
+
+    #[test]
+    fn invalid_shares_err() {
+        let sss = SecretSharing::<POLY>(3);
+        let shares = deal(&sss, &[1, 2], 4);
+        let longer = deal(&sss, &[1, 2, 3], 4);
+
+        let mut combiner = sss.combiner();
+        assert!(combiner.finalize().is_err());
+        assert_eq!(combiner.add(0, &shares[0]), Ok(2));
+        assert!(combiner.add(1, &longer[1]).is_err());
+        assert!(combiner.add(0, &shares[0]).is_err());
+        #[cfg(not(feature = "share_x"))]
+        {
+            assert!(combiner.add(0, &shares[1]).is_err());
+            assert!(combiner.add(255, &shares[1]).is_err());
+        }
+        #[cfg(feature = "share_x")]
+        {
+            let mut conflicting = shares[0].clone();
+            conflicting.y[0].0 ^= 1;
+            assert!(combiner.add(1, &conflicting).is_err());
+        }
+        // Rejected shares leave the combiner unchanged
+        assert_eq!(combiner.remaining(), 2);
+        assert_eq!(combiner.add(1, &shares[1]), Ok(1));
+        assert_eq!(combiner.add(3, &shares[3]), Ok(0));
+        assert!(combiner.add(2, &shares[2]).is_err());
+        assert_eq!(combiner.finalize().unwrap(), [1, 2]);
+    }
+}
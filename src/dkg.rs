@@ -0,0 +1,766 @@
+// Dealerless distributed key generation (Joint-Feldman style, with blinded consistency checks in
+// place of commitments). Each of the `n` parties deals a random contribution with `dealer_rng`, along
+// with a random mask, and sends a sub-share to every party. The joint secret is the sum of the
+// contributions of the qualified dealers, and each party's share the sum of the sub-shares it
+// received from them, so that no party ever holds the joint secret.
+//
+// To detect dealers handing out sub-shares that do not lie on a single polynomial, every party
+// broadcasts its sub-shares blinded with their masks, `y + c_j * mask_j`, for `DKG_CHALLENGE_LENGTH`
+// non-zero public challenges `c_j` agreed upon once all sub-shares were sent, each with its own
+// independent mask. The blinded values are a random sharing of their own, so they can be checked for
+// consistency (see `check_consistency`) without revealing anything. An inconsistent dealer passes
+// each check for at most one challenge out of 255, so all of them with probability at most
+// `255^-16`, below `2^-127`.
+//
+// A party whose blinded sub-share is off the polynomial of the others complains against the dealer,
+// which answers by publishing the disputed sub-share and mask. The published values, blinded with
+// the challenge, must then lie on a single polynomial along with the undisputed blinded sub-shares,
+// otherwise the dealer is disqualified. Nothing binds a dealer to what it sent privately, so a
+// mismatch between the published and the received sub-share cannot be blamed on either side: the
+// complaining party is never disqualified, and uses the published sub-share instead.
+
+use alloc::vec::Vec;
+use hashbrown::HashSet;
+
+#[cfg(feature = "zeroize_memory")]
+use zeroize::Zeroize;
+
+use super::field::GF256;
+use super::share::{new_share, Share};
+use super::SecretSharing;
+
+/// Number of independent challenges, and masks, of the consistency check of a DKG.
+pub const DKG_CHALLENGE_LENGTH: usize = 16;
+
+/// A sub-share of the contribution of the dealer at `from`, sent privately to the party at `to`.
+#[derive(Clone)]
+#[cfg_attr(feature = "zeroize_memory", derive(Zeroize))]
+#[cfg_attr(feature = "zeroize_memory", zeroize(drop))]
+pub struct DkgMessage<const POLY: u16> {
+    /// The x coordinate of the dealer.
+    pub from: u8,
+    /// The x coordinate of the receiving party.
+    pub to: u8,
+    /// The sub-share of the dealer's contribution.
+    pub y: Vec<GF256<POLY>>,
+    /// The sub-shares of the dealer's `DKG_CHALLENGE_LENGTH` masks, concatenated, only used by the
+    /// complaint phase.
+    pub mask: Vec<GF256<POLY>>,
+}
+
+/// A sub-share blinded with its mask, broadcast by the party at `to` during the complaint phase.
+#[derive(Clone)]
+pub struct DkgBlindedShare<const POLY: u16> {
+    /// The x coordinate of the dealer.
+    pub from: u8,
+    /// The x coordinate of the broadcasting party.
+    pub to: u8,
+    /// The sub-share blinded with each challenge and mask, `y + c_j * mask_j`, concatenated.
+    pub y: Vec<GF256<POLY>>,
+}
+
+/// A complaint of the party at `from` against the dealer at `against`, whose sub-share it received
+/// is inconsistent with the others. The dealer must answer it with a [`DkgResponse`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DkgComplaint {
+    /// The x coordinate of the complaining party.
+    pub from: u8,
+    /// The x coordinate of the accused dealer.
+    pub against: u8,
+}
+
+/// The answer of the dealer at `from` to a complaint of the party at `to`: the sub-share and mask it
+/// sent to that party, which become public.
+#[derive(Clone)]
+pub struct DkgResponse<const POLY: u16> {
+    /// The x coordinate of the dealer.
+    pub from: u8,
+    /// The x coordinate of the complaining party.
+    pub to: u8,
+    /// The disputed sub-share of the dealer's contribution.
+    pub y: Vec<GF256<POLY>>,
+    /// The disputed sub-shares of the dealer's masks.
+    pub mask: Vec<GF256<POLY>>,
+}
+
+/// Returns `y + c_j * mask_j` for each challenge `c_j` and mask `mask_j`, concatenated.
+fn blind<const POLY: u16>(
+    challenge: &[u8; DKG_CHALLENGE_LENGTH],
+    y: &[GF256<POLY>],
+    mask: &[GF256<POLY>],
+) -> Vec<GF256<POLY>> {
+    challenge
+        .iter()
+        .zip(mask.chunks(y.len().max(1)))
+        .flat_map(|(&c, mask)| {
+            y.iter()
+                .zip(mask.iter())
+                .map(move |(y, mask)| y.clone() + GF256(c) * mask.clone())
+        })
+        .collect()
+}
+
+/// Places the blinded sub-shares of `dealer` at the positions of the parties they come from.
+fn blinded_shares<const POLY: u16>(
+    dealer: u8,
+    blinded: &[&DkgBlindedShare<POLY>],
+) -> Result<Vec<Option<Share<POLY>>>, &'static str> {
+    let mut shares: Vec<Option<Share<POLY>>> = Vec::new();
+    for b in blinded.iter().filter(|b| b.from == dealer) {
+        if b.to == 0 {
+            return Err("Parties x coordinates must not be 0");
+        }
+        if shares.len() < b.to as usize {
+            shares.resize(b.to as usize, None);
+        }
+        if shares[b.to as usize - 1].is_some() {
+            return Err("Blinded sub-shares must be from distinct parties");
+        }
+        shares[b.to as usize - 1] = Some(new_share(GF256(b.to), b.y.clone()));
+    }
+    Ok(shares)
+}
+
+/// Checks that the messages come from distinct senders, are addressed to `to` and share a length.
+fn check_messages<const POLY: u16>(
+    to: u8,
+    messages: &[&DkgMessage<POLY>],
+) -> Result<(), &'static str> {
+    let mut senders = HashSet::new();
+    if !messages
+        .iter()
+        .all(|m| m.to == to && m.from != 0 && senders.insert(m.from))
+    {
+        return Err("DKG messages must be addressed to the party and from distinct dealers");
+    }
+    if messages.iter().any(|m| {
+        m.y.len() != messages[0].y.len() || m.mask.len() != DKG_CHALLENGE_LENGTH * m.y.len()
+    }) {
+        return Err("All DKG messages must have the same length");
+    }
+    Ok(())
+}
+
+impl<const POLY: u16> SecretSharing<POLY> {
+    /// First round of a DKG, run by the dealer at `from`: deals a random contribution of `length`
+    /// bytes, along with `DKG_CHALLENGE_LENGTH` random masks, to the `n` parties at `x = 1..=n`
+    /// (including itself).
+    /// A random number generator has to be provided.
+    pub fn dkg_messages_rng<R: rand::Rng>(
+        &self,
+        from: u8,
+        length: usize,
+        n: u8,
+        rng: &mut R,
+    ) -> Result<Vec<DkgMessage<POLY>>, &'static str> {
+        if self.0 == 0 || n < self.0 {
+            return Err("A threshold of at least 1 and at most n is required");
+        }
+        if from == 0 || from > n {
+            return Err("The dealer must be one of the n parties");
+        }
+
+        let mut contribution = alloc::vec![0u8; length];
+        rng.fill(contribution.as_mut_slice());
+        let mut mask = alloc::vec![0u8; DKG_CHALLENGE_LENGTH * length];
+        rng.fill(mask.as_mut_slice());
+
+        let messages = self
+            .dealer_rng(&contribution, rng)
+            .zip(self.dealer_rng(&mask, rng))
+            .zip(1..=n)
+            .map(|((share, mask), to)| DkgMessage {
+                from,
+                to,
+                y: share.y.clone(),
+                mask: mask.y.clone(),
+            })
+            .collect();
+
+        #[cfg(feature = "zeroize_memory")]
+        {
+            contribution.zeroize();
+            mask.zeroize();
+        }
+
+        Ok(messages)
+    }
+
+    /// Same as `dkg_messages_rng`, using `rand::thread_rng`.
+    #[cfg(feature = "std")]
+    pub fn dkg_messages(
+        &self,
+        from: u8,
+        length: usize,
+        n: u8,
+    ) -> Result<Vec<DkgMessage<POLY>>, &'static str> {
+        let mut rng = rand::thread_rng();
+        self.dkg_messages_rng(from, length, n, &mut rng)
+    }
+
+    /// Complaint phase of a DKG, run by the party at `to` once every sub-share was sent: blinds the
+    /// received sub-shares with the public `challenge`, whose bytes must not be 0, for broadcasting.
+    pub fn dkg_blind<'a, T>(
+        &self,
+        to: u8,
+        challenge: &[u8; DKG_CHALLENGE_LENGTH],
+        messages: T,
+    ) -> Result<Vec<DkgBlindedShare<POLY>>, &'static str>
+    where
+        T: IntoIterator<Item = &'a DkgMessage<POLY>>,
+        T::IntoIter: Iterator<Item = &'a DkgMessage<POLY>>,
+    {
+        if challenge.contains(&0) {
+            return Err("The challenges must not be 0, it would reveal the sub-shares");
+        }
+        let messages: Vec<&DkgMessage<POLY>> = messages.into_iter().collect();
+        check_messages(to, &messages)?;
+
+        Ok(messages
+            .iter()
+            .map(|m| DkgBlindedShare {
+                from: m.from,
+                to,
+                y: blind(challenge, &m.y, &m.mask),
+            })
+            .collect())
+    }
+
+    /// Complaint phase of a DKG, run by every party on the broadcast blinded sub-shares: returns
+    /// the complaints of the parties whose blinded sub-share is off the polynomial of the others.
+    /// If a dealer has too many inconsistent sub-shares to single them out, every party complains
+    /// against it, and its responses settle which broadcasts were false (see `dkg_disqualified`).
+    pub fn dkg_complaints<'a, T>(&self, blinded: T) -> Result<Vec<DkgComplaint>, &'static str>
+    where
+        T: IntoIterator<Item = &'a DkgBlindedShare<POLY>>,
+        T::IntoIter: Iterator<Item = &'a DkgBlindedShare<POLY>>,
+    {
+        let blinded: Vec<&DkgBlindedShare<POLY>> = blinded.into_iter().collect();
+        let mut dealers: Vec<u8> = blinded.iter().map(|b| b.from).collect();
+        dealers.sort_unstable();
+        dealers.dedup();
+
+        let mut complaints = Vec::new();
+        for dealer in dealers {
+            let shares = blinded_shares(dealer, &blinded)?;
+            // Any `k` sub-shares lie on a polynomial of degree `k - 1`, so they cannot be inconsistent
+            if shares.iter().flatten().count() <= self.0 as usize {
+                continue;
+            }
+            let inconsistent = self
+                .check_consistency(&shares)
+                .unwrap_or_else(|_| (0..shares.len()).filter(|&i| shares[i].is_some()).collect());
+            complaints.extend(inconsistent.into_iter().map(|i| DkgComplaint {
+                from: i as u8 + 1,
+                against: dealer,
+            }));
+        }
+        Ok(complaints)
+    }
+
+    /// Response phase of a DKG, run by the dealer at `from`: answers each complaint against it with
+    /// the sub-share and mask it sent to the complaining party, among its `messages`.
+    pub fn dkg_respond(
+        &self,
+        from: u8,
+        messages: &[DkgMessage<POLY>],
+        complaints: &[DkgComplaint],
+    ) -> Result<Vec<DkgResponse<POLY>>, &'static str> {
+        let mut complainers: Vec<u8> = complaints
+            .iter()
+            .filter(|c| c.against == from)
+            .map(|c| c.from)
+            .collect();
+        complainers.sort_unstable();
+        complainers.dedup();
+
+        complainers
+            .into_iter()
+            .map(|to| {
+                let m = messages
+                    .iter()
+                    .find(|m| m.from == from && m.to == to)
+                    .ok_or("No DKG message was sent to the complaining party")?;
+                Ok(DkgResponse {
+                    from,
+                    to,
+                    y: m.y.clone(),
+                    mask: m.mask.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Response phase of a DKG, run by every party: checks the responses of the dealers to the
+    /// complaints against them, with the `challenge` and the blinded sub-shares of the complaint
+    /// phase. Returns the disqualified dealers, sorted: the ones that did not answer every
+    /// complaint, or whose published sub-shares are inconsistent with the undisputed ones.
+    /// Complaints answered with consistent sub-shares are dismissed, without disqualifying the
+    /// complaining party, which cannot be told apart from one that received another sub-share.
+    pub fn dkg_disqualified<'a, T>(
+        &self,
+        challenge: &[u8; DKG_CHALLENGE_LENGTH],
+        blinded: T,
+        complaints: &[DkgComplaint],
+        responses: &[DkgResponse<POLY>],
+    ) -> Result<Vec<u8>, &'static str>
+    where
+        T: IntoIterator<Item = &'a DkgBlindedShare<POLY>>,
+        T::IntoIter: Iterator<Item = &'a DkgBlindedShare<POLY>>,
+    {
+        let blinded: Vec<&DkgBlindedShare<POLY>> = blinded.into_iter().collect();
+        if !complaints.iter().all(|c| {
+            blinded
+                .iter()
+                .any(|b| b.from == c.against && b.to == c.from)
+        }) {
+            return Err("Complaints must come from parties with a blinded sub-share of the dealer");
+        }
+        let mut dealers: Vec<u8> = complaints.iter().map(|c| c.against).collect();
+        dealers.sort_unstable();
+        dealers.dedup();
+
+        let mut disqualified = Vec::new();
+        for dealer in dealers {
+            let mut shares = blinded_shares(dealer, &blinded)?;
+            let complainers: HashSet<u8> = complaints
+                .iter()
+                .filter(|c| c.against == dealer)
+                .map(|c| c.from)
+                .collect();
+
+            let mut answered = true;
+            for &to in &complainers {
+                let response = responses.iter().find(|r| r.from == dealer && r.to == to);
+                let published = match response {
+                    Some(r) if r.mask.len() == DKG_CHALLENGE_LENGTH * r.y.len() => {
+                        blind(challenge, &r.y, &r.mask)
+                    }
+                    _ => {
+                        answered = false;
+                        break;
+                    }
+                };
+                shares[to as usize - 1] = Some(new_share(GF256(to), published));
+            }
+
+            // Any `k` sub-shares lie on a polynomial of degree `k - 1`, so they cannot be inconsistent
+            let consistent = answered
+                && (shares.iter().flatten().count() <= self.0 as usize
+                    || self
+                        .check_consistency(&shares)
+                        .is_ok_and(|inconsistent| inconsistent.is_empty()));
+            if !consistent {
+                disqualified.push(dealer);
+            }
+        }
+        Ok(disqualified)
+    }
+
+    /// Last round of a DKG, run by the party at `to`: sums the sub-shares received from the dealers
+    /// that were not disqualified into its share of the joint secret. The sub-shares published in
+    /// `responses` to the complaints of the party replace the ones it received.
+    /// Without `share_x`, the share must be placed at position `to - 1`.
+    pub fn dkg_combine<'a, T>(
+        &self,
+        to: u8,
+        messages: T,
+        responses: &[DkgResponse<POLY>],
+        disqualified: &[u8],
+    ) -> Result<Share<POLY>, &'static str>
+    where
+        T: IntoIterator<Item = &'a DkgMessage<POLY>>,
+        T::IntoIter: Iterator<Item = &'a DkgMessage<POLY>>,
+    {
+        let messages: Vec<&DkgMessage<POLY>> = messages
+            .into_iter()
+            .filter(|m| !disqualified.contains(&m.from))
+            .collect();
+        if messages.is_empty() {
+            return Err("No qualified dealer left to combine a share");
+        }
+        check_messages(to, &messages)?;
+
+        let sub_shares: Vec<&Vec<GF256<POLY>>> = messages
+            .iter()
+            .map(|m| {
+                responses
+                    .iter()
+                    .find(|r| r.from == m.from && r.to == to)
+                    .map_or(&m.y, |r| &r.y)
+            })
+            .collect();
+        if sub_shares.iter().any(|y| y.len() != messages[0].y.len()) {
+            return Err("All DKG messages must have the same length");
+        }
+
+        let y = (0..messages[0].y.len())
+            .map(|i| sub_shares.iter().map(|y| y[i].clone()).sum())
+            .collect();
+        Ok(new_share(GF256(to), y))
+    }
+
+    /// Runs a whole DKG in-process among `n` parties at `x = 1..=n`, as each party would in the
+    /// protocol, and returns their shares of a random joint secret of `length` bytes.
+    ///
+    /// Example:
+    /// ```
+    /// # use ssskit::{ SecretSharing, Share };
+    /// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    /// # const POLY: u16 = 0x11d_u16;
+    /// # let sss = SecretSharing::<POLY>(3);
+    /// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// let mut shares = sss
+    ///     .dkg_rng(5, 32, &mut rng)
+    ///     .unwrap()
+    ///     .into_iter()
+    ///     .map(Some)
+    ///     .collect::<Vec<Option<Share<POLY>>>>();
+    /// let key = sss.recover(&shares).unwrap();
+    /// shares[0] = None;
+    /// shares[3] = None;
+    /// assert_eq!(sss.recover(&shares).unwrap(), key);
+    /// ```
+    pub fn dkg_rng<R: rand::Rng>(
+        &self,
+        n: u8,
+        length: usize,
+        rng: &mut R,
+    ) -> Result<Vec<Share<POLY>>, &'static str> {
+        let parties: Vec<u8> = (1..=n).collect();
+        let mut messages = Vec::with_capacity(n as usize * n as usize);
+        for &from in &parties {
+            messages.extend(self.dkg_messages_rng(from, length, n, rng)?);
+        }
+
+        let mut challenge = [0u8; DKG_CHALLENGE_LENGTH];
+        for c in challenge.iter_mut() {
+            *c = rng.gen_range(1..=u8::MAX);
+        }
+        let mut blinded = Vec::with_capacity(messages.len());
+        for &to in &parties {
+            blinded.extend(self.dkg_blind(
+                to,
+                &challenge,
+                messages.iter().filter(|m| m.to == to),
+            )?);
+        }
+        let complaints = self.dkg_complaints(&blinded)?;
+
+        let mut responses = Vec::new();
+        for &from in &parties {
+            responses.extend(self.dkg_respond(from, &messages, &complaints)?);
+        }
+        let disqualified = self.dkg_disqualified(&challenge, &blinded, &complaints, &responses)?;
+
+        parties
+            .iter()
+            .map(|&to| {
+                self.dkg_combine(
+                    to,
+                    messages.iter().filter(|m| m.to == to),
+                    &responses,
+                    &disqualified,
+                )
+            })
+            .collect()
+    }
+
+    /// Same as `dkg_rng`, using `rand::thread_rng`.
+    #[cfg(feature = "std")]
+    pub fn dkg(&self, n: u8, length: usize) -> Result<Vec<Share<POLY>>, &'static str> {
+        let mut rng = rand::thread_rng();
+        self.dkg_rng(n, length, &mut rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DkgBlindedShare, DkgComplaint, DkgMessage, DKG_CHALLENGE_LENGTH};
+    use crate::{SecretSharing, Share};
+    use alloc::{vec, vec::Vec};
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    use rstest::rstest;
+
+    const POLY: u16 = 0x11d_u16;
+
+    const CHALLENGE: [u8; DKG_CHALLENGE_LENGTH] = [
+        0x17, 0x8e, 0x02, 0xd3, 0x5b, 0xf0, 0x21, 0x9c, 0x44, 0xa7, 0x6d, 0x01, 0xbe, 0x39, 0xc5,
+        0x72,
+    ];
+
+    fn deal(sss: &SecretSharing<POLY>, n: u8, length: usize) -> Vec<DkgMessage<POLY>> {
+        let mut rng = ChaCha8Rng::from_seed([0x44; 32]);
+        (1..=n)
+            .flat_map(|from| sss.dkg_messages_rng(from, length, n, &mut rng).unwrap())
+            .collect()
+    }
+
+    /// Recovers the contribution of `dealer` from the sub-shares it sent.
+    fn contribution(
+        sss: &SecretSharing<POLY>,
+        messages: &[DkgMessage<POLY>],
+        dealer: u8,
+    ) -> Vec<u8> {
+        let shares: Vec<Option<Share<POLY>>> = messages
+            .iter()
+            .filter(|m| m.from == dealer)
+            .map(|m| Some(super::new_share(super::GF256(m.to), m.y.clone())))
+            .collect();
+        sss.recover(&shares).unwrap()
+    }
+
+    /// Sums the contributions of `dealers`.
+    fn joint_secret(
+        sss: &SecretSharing<POLY>,
+        messages: &[DkgMessage<POLY>],
+        dealers: impl IntoIterator<Item = u8>,
+    ) -> Vec<u8> {
+        let mut secret = vec![0u8; messages[0].y.len()];
+        for dealer in dealers {
+            for (s, c) in secret.iter_mut().zip(contribution(sss, messages, dealer)) {
+                *s ^= c;
+            }
+        }
+        secret
+    }
+
+    /// Runs the rounds after dealing `messages`, with dealers answering complaints from `published`
+    /// and `forge` tampering with the broadcast blinded sub-shares. Returns the complaints, the
+    /// disqualified parties and the shares of the parties.
+    fn run(
+        sss: &SecretSharing<POLY>,
+        n: u8,
+        messages: &[DkgMessage<POLY>],
+        published: &[DkgMessage<POLY>],
+        challenge: &[u8; DKG_CHALLENGE_LENGTH],
+        forge: fn(&mut [DkgBlindedShare<POLY>]),
+    ) -> (Vec<DkgComplaint>, Vec<u8>, Vec<Option<Share<POLY>>>) {
+        let mut blinded: Vec<_> = (1..=n)
+            .flat_map(|to| {
+                sss.dkg_blind(to, challenge, messages.iter().filter(|m| m.to == to))
+                    .unwrap()
+            })
+            .collect();
+        forge(&mut blinded);
+        let complaints = sss.dkg_complaints(&blinded).unwrap();
+        let responses: Vec<_> = (1..=n)
+            .flat_map(|from| sss.dkg_respond(from, published, &complaints).unwrap())
+            .collect();
+        let disqualified = sss
+            .dkg_disqualified(challenge, &blinded, &complaints, &responses)
+            .unwrap();
+        let shares = (1..=n)
+            .map(|to| {
+                sss.dkg_combine(
+                    to,
+                    messages.iter().filter(|m| m.to == to),
+                    &responses,
+                    &disqualified,
+                )
+                .ok()
+            })
+            .collect();
+        (complaints, disqualified, shares)
+    }
+
+    #[rstest]
+    #[case(1, 1)]
+    #[case(2, 3)]
+    #[case(3, 5)]
+    #[case(5, 5)]
+    #[case(7, 20)]
+    fn joint_secret_is_sum_of_contributions(#[case] k: u8, #[case] n: u8) {
+        let sss = SecretSharing::<POLY>(k);
+        let messages = deal(&sss, n, 16);
+        let (complaints, disqualified, mut shares) = run(
+            &sss,
+            n,
+            &messages,
+            &messages,
+            &[0x5c; DKG_CHALLENGE_LENGTH],
+            |_| {},
+        );
+        assert!(complaints.is_empty());
+        assert!(disqualified.is_empty());
+
+        let expected = joint_secret(&sss, &messages, 1..=n);
+        assert_eq!(sss.recover(&shares).unwrap(), expected);
+        for share in shares.iter_mut().take((n - k) as usize) {
+            *share = None;
+        }
+        assert_eq!(sss.recover(&shares).unwrap(), expected);
+    }
+
+    #[test]
+    fn inconsistent_dealer_is_disqualified() {
+        let sss = SecretSharing::<POLY>(2);
+        let mut messages = deal(&sss, 5, 4);
+        // The third dealer sends an inconsistent sub-share to the fourth party
+        let cheat = messages
+            .iter_mut()
+            .find(|m| m.from == 3 && m.to == 4)
+            .unwrap();
+        cheat.y[1].0 ^= 0x21;
+
+        let (complaints, disqualified, shares) =
+            run(&sss, 5, &messages, &messages, &CHALLENGE, |_| {});
+        assert_eq!(
+            complaints,
+            vec![DkgComplaint {
+                from: 4,
+                against: 3
+            }]
+        );
+        // The published sub-share is the inconsistent one
+        assert_eq!(disqualified, vec![3]);
+
+        let expected = joint_secret(&sss, &messages, [1, 2, 4, 5]);
+        assert_eq!(sss.recover(&shares).unwrap(), expected);
+        assert!(sss.check_consistency(&shares).unwrap().is_empty());
+    }
+
+    #[test]
+    fn cheating_dealer_cannot_disqualify_complainer() {
+        let sss = SecretSharing::<POLY>(2);
+        let published = deal(&sss, 5, 4);
+        // The third dealer sends a corrupted sub-share to the fourth party, and publishes the
+        // consistent one when it complains
+        let mut messages = published.clone();
+        let cheat = messages
+            .iter_mut()
+            .find(|m| m.from == 3 && m.to == 4)
+            .unwrap();
+        cheat.y[0].0 ^= 0x42;
+
+        let (complaints, disqualified, shares) =
+            run(&sss, 5, &messages, &published, &CHALLENGE, |_| {});
+        assert_eq!(
+            complaints,
+            vec![DkgComplaint {
+                from: 4,
+                against: 3
+            }]
+        );
+        assert!(disqualified.is_empty());
+
+        // The complaining party uses the published sub-share
+        assert!(shares.iter().all(Option::is_some));
+        let expected = joint_secret(&sss, &published, 1..=5);
+        assert_eq!(sss.recover(&shares).unwrap(), expected);
+        assert!(sss.check_consistency(&shares).unwrap().is_empty());
+    }
+
+    #[test]
+    fn false_complaint_is_dismissed() {
+        let sss = SecretSharing::<POLY>(2);
+        let messages = deal(&sss, 5, 4);
+        // The fourth party broadcasts a wrong blinded sub-share to frame the second dealer
+        let (complaints, disqualified, shares) =
+            run(&sss, 5, &messages, &messages, &CHALLENGE, |blinded| {
+                let forged = blinded
+                    .iter_mut()
+                    .find(|b| b.from == 2 && b.to == 4)
+                    .unwrap();
+                forged.y[0].0 ^= 0x5a;
+            });
+        assert_eq!(
+            complaints,
+            vec![DkgComplaint {
+                from: 4,
+                against: 2
+            }]
+        );
+        // The published sub-share checks out, so nobody is disqualified
+        assert!(disqualified.is_empty());
+
+        let expected = joint_secret(&sss, &messages, 1..=5);
+        assert_eq!(sss.recover(&shares).unwrap(), expected);
+        assert!(sss.check_consistency(&shares).unwrap().is_empty());
+    }
+
+    #[test]
+    fn unanswered_complaint_disqualifies_dealer() {
+        let sss = SecretSharing::<POLY>(2);
+        let messages = deal(&sss, 4, 4);
+        let blinded: Vec<_> = (1..=4)
+            .flat_map(|to| {
+                sss.dkg_blind(to, &CHALLENGE, messages.iter().filter(|m| m.to == to))
+                    .unwrap()
+            })
+            .collect();
+        let complaints = [DkgComplaint {
+            from: 1,
+            against: 3,
+        }];
+        let responses = sss.dkg_respond(3, &messages, &complaints).unwrap();
+        assert_eq!(responses.len(), 1);
+
+        assert_eq!(
+            sss.dkg_disqualified(&CHALLENGE, &blinded, &complaints, &[])
+                .unwrap(),
+            vec![3]
+        );
+        // The published sub-share matches the broadcast one, so the complaint is dismissed
+        assert!(sss
+            .dkg_disqualified(&CHALLENGE, &blinded, &complaints, &responses)
+            .unwrap()
+            .is_empty());
+        // Complaints from parties without a blinded sub-share are rejected
+        let unknown = [DkgComplaint {
+            from: 7,
+            against: 3,
+        }];
+        assert!(sss
+            .dkg_disqualified(&CHALLENGE, &blinded, &unknown, &responses)
+            .is_err());
+    }
+
+    #[test]
+    fn harness_shares_are_consistent() {
+        let sss = SecretSharing::<POLY>(3);
+        let mut rng = ChaCha8Rng::from_seed([0x45; 32]);
+        let shares: Vec<Option<Share<POLY>>> = sss
+            .dkg_rng(6, 8, &mut rng)
+            .unwrap()
+            .into_iter()
+            .map(Some)
+            .collect();
+        assert!(sss.check_consistency(&shares).unwrap().is_empty());
+        let other: Vec<Option<Share<POLY>>> = sss
+            .dkg_rng(6, 8, &mut rng)
+            .unwrap()
+            .into_iter()
+            .map(Some)
+            .collect();
+        assert_ne!(sss.recover(&shares).unwrap(), sss.recover(&other).unwrap());
+    }
+
+    #[test]
+    fn invalid_parameters_err() {
+        let sss = SecretSharing::<POLY>(3);
+        let mut rng = ChaCha8Rng::from_seed([0x45; 32]);
+        assert!(sss.dkg_messages_rng(1, 4, 2, &mut rng).is_err());
+        assert!(sss.dkg_messages_rng(0, 4, 3, &mut rng).is_err());
+        assert!(sss.dkg_messages_rng(4, 4, 3, &mut rng).is_err());
+
+        let messages = deal(&sss, 3, 4);
+        let received: Vec<&DkgMessage<POLY>> = messages.iter().filter(|m| m.to == 1).collect();
+        let mut zero = CHALLENGE;
+        zero[5] = 0;
+        assert!(sss.dkg_blind(1, &zero, received.iter().copied()).is_err());
+        assert!(sss
+            .dkg_blind(2, &CHALLENGE, received.iter().copied())
+            .is_err());
+        assert!(sss
+            .dkg_combine(1, [received[0], received[0]], &[], &[])
+            .is_err());
+        assert!(sss.dkg_combine(1, received, &[], &[1, 2, 3]).is_err());
+        // A dealer cannot answer a complaint from a party it sent nothing to
+        let complaints = [DkgComplaint {
+            from: 4,
+            against: 1,
+        }];
+        assert!(sss.dkg_respond(1, &messages, &complaints).is_err());
+    }
+}
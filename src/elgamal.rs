@@ -0,0 +1,519 @@
+//! Threshold ElGamal decryption over ristretto255.
+//!
+//! The private key is shared with Shamir's secret sharing over the scalar field, so that any `k`
+//! holders can decrypt a ciphertext `(c1, c2) = (r * G, M + r * P)` without reconstructing the key.
+//! Each holder publishes a partial decryption `s_i * c1` along with a DLEQ (Chaum-Pedersen) proof
+//! that it used the same share as in its verification key `s_i * G`, and the combiner interpolates
+//! `s * c1` in the exponent with Lagrange coefficients.
+
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use curve25519_dalek::ristretto::{CompressedRistretto, RistrettoPoint};
+use curve25519_dalek::scalar::Scalar;
+use curve25519_dalek::traits::IsIdentity;
+use hashbrown::HashSet;
+use sha2::{Digest, Sha512};
+
+#[cfg(feature = "zeroize_memory")]
+use zeroize::Zeroize;
+
+use super::scalar;
+
+/// Domain separator of the DLEQ proofs.
+const DLEQ_DOMAIN: &[u8] = b"ssskit-threshold-elgamal-ristretto255-dleq-v1";
+
+/// Length in bytes of a serialized group element or scalar.
+const ELEMENT_LENGTH: usize = 32;
+
+fn deserialize_element(bytes: &[u8]) -> Result<RistrettoPoint, &'static str> {
+    CompressedRistretto::from_slice(bytes)
+        .map_err(|_| "A group element must be 32 bytes long")?
+        .decompress()
+        .filter(|element| !element.is_identity())
+        .ok_or("Invalid group element")
+}
+
+fn deserialize_scalar(bytes: &[u8]) -> Result<Scalar, &'static str> {
+    let bytes: [u8; ELEMENT_LENGTH] = bytes
+        .try_into()
+        .map_err(|_| "A scalar must be 32 bytes long")?;
+    Option::from(Scalar::from_canonical_bytes(bytes)).ok_or("Invalid scalar")
+}
+
+/// Returns the Fiat-Shamir challenge of a DLEQ proof that `log_G(v) = log_c1(d)`, with commitments
+/// `a = w * G` and `b = w * c1`.
+fn dleq_challenge(
+    v: &RistrettoPoint,
+    c1: &RistrettoPoint,
+    d: &RistrettoPoint,
+    a: &RistrettoPoint,
+    b: &RistrettoPoint,
+) -> Scalar {
+    let mut hasher = Sha512::new();
+    hasher.update(DLEQ_DOMAIN);
+    for element in [v, c1, d, a, b] {
+        hasher.update(element.compress().as_bytes());
+    }
+    Scalar::from_bytes_mod_order_wide(&hasher.finalize().into())
+}
+
+/// The public key of the group, along with the verification keys of the holders.
+#[derive(Clone)]
+pub struct ElGamalPublicKey {
+    /// The public key `P = s * G` to encrypt with.
+    pub public_key: RistrettoPoint,
+    /// The verification key `s_i * G` of the holder at `i`, at position `i - 1`.
+    pub verification_keys: Vec<RistrettoPoint>,
+}
+
+/// The share of the private key of the holder at `index`.
+///
+/// Can be serialized to and from a byte array as `[index, share (32 bytes)]`.
+#[derive(Clone)]
+#[cfg_attr(feature = "zeroize_memory", derive(Zeroize))]
+#[cfg_attr(feature = "zeroize_memory", zeroize(drop))]
+pub struct ElGamalKeyShare {
+    /// The index of the holder, its x coordinate.
+    pub index: u8,
+    /// The share of the private key.
+    pub share: Scalar,
+}
+
+impl From<&ElGamalKeyShare> for Vec<u8> {
+    fn from(s: &ElGamalKeyShare) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(1 + ELEMENT_LENGTH);
+        bytes.push(s.index);
+        bytes.extend_from_slice(s.share.as_bytes());
+        bytes
+    }
+}
+
+impl TryFrom<&[u8]> for ElGamalKeyShare {
+    type Error = &'static str;
+
+    fn try_from(s: &[u8]) -> Result<ElGamalKeyShare, Self::Error> {
+        if s.len() != 1 + ELEMENT_LENGTH || s[0] == 0 {
+            return Err("Invalid key share");
+        }
+        Ok(ElGamalKeyShare {
+            index: s[0],
+            share: deserialize_scalar(&s[1..])?,
+        })
+    }
+}
+
+/// An ElGamal ciphertext `(c1, c2) = (r * G, M + r * P)` of the group element `M`.
+///
+/// Can be serialized to and from a byte array as `[c1, c2]`, each 32 bytes long.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ciphertext {
+    /// The ephemeral key `r * G`.
+    pub c1: RistrettoPoint,
+    /// The masked message `M + r * P`.
+    pub c2: RistrettoPoint,
+}
+
+impl From<&Ciphertext> for Vec<u8> {
+    fn from(c: &Ciphertext) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(2 * ELEMENT_LENGTH);
+        bytes.extend_from_slice(c.c1.compress().as_bytes());
+        bytes.extend_from_slice(c.c2.compress().as_bytes());
+        bytes
+    }
+}
+
+impl TryFrom<&[u8]> for Ciphertext {
+    type Error = &'static str;
+
+    fn try_from(s: &[u8]) -> Result<Ciphertext, Self::Error> {
+        if s.len() != 2 * ELEMENT_LENGTH {
+            return Err("A ciphertext must be 64 bytes long");
+        }
+        Ok(Ciphertext {
+            c1: deserialize_element(&s[..ELEMENT_LENGTH])?,
+            // The message may be the identity
+            c2: CompressedRistretto::from_slice(&s[ELEMENT_LENGTH..])
+                .map_err(|_| "A group element must be 32 bytes long")?
+                .decompress()
+                .ok_or("Invalid group element")?,
+        })
+    }
+}
+
+/// The partial decryption `s_i * c1` of a ciphertext by the holder at `index`, with a DLEQ proof
+/// that it matches the verification key of the holder.
+///
+/// Can be serialized to and from a byte array as `[index, d, challenge, response]`, where each
+/// group element and scalar is 32 bytes long.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PartialDecryption {
+    /// The index of the holder.
+    pub index: u8,
+    /// The partial decryption `s_i * c1`.
+    pub d: RistrettoPoint,
+    /// The challenge of the DLEQ proof.
+    pub challenge: Scalar,
+    /// The response of the DLEQ proof.
+    pub response: Scalar,
+}
+
+impl From<&PartialDecryption> for Vec<u8> {
+    fn from(p: &PartialDecryption) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(1 + 3 * ELEMENT_LENGTH);
+        bytes.push(p.index);
+        bytes.extend_from_slice(p.d.compress().as_bytes());
+        bytes.extend_from_slice(p.challenge.as_bytes());
+        bytes.extend_from_slice(p.response.as_bytes());
+        bytes
+    }
+}
+
+impl TryFrom<&[u8]> for PartialDecryption {
+    type Error = &'static str;
+
+    fn try_from(s: &[u8]) -> Result<PartialDecryption, Self::Error> {
+        if s.len() != 1 + 3 * ELEMENT_LENGTH || s[0] == 0 {
+            return Err("Invalid partial decryption");
+        }
+        Ok(PartialDecryption {
+            index: s[0],
+            d: deserialize_element(&s[1..33])?,
+            challenge: deserialize_scalar(&s[33..65])?,
+            response: deserialize_scalar(&s[65..97])?,
+        })
+    }
+}
+
+/// Struct which implements threshold ElGamal decryption over ristretto255, where the tuple
+/// parameter is the threshold `k`.
+///
+/// Usage example:
+/// ```
+/// # use ssskit::ElGamal;
+/// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+/// use curve25519_dalek::ristretto::RistrettoPoint;
+///
+/// let elgamal = ElGamal(3);
+/// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+/// let (public, shares) = elgamal.dealer_rng(5, &mut rng).unwrap();
+///
+/// let message = RistrettoPoint::from_uniform_bytes(&[42; 64]);
+/// let ciphertext = ElGamal::encrypt_rng(&public, &message, &mut rng);
+/// // Any 3 holders decrypt without the private key
+/// let decrypted = elgamal.decrypt_rng(&shares[2..], &ciphertext, &public, &mut rng).unwrap();
+/// assert_eq!(decrypted, message);
+/// ```
+pub struct ElGamal(pub u8);
+
+impl ElGamal {
+    /// Generates a random private key and shares it with threshold `k` among `n` holders at indices
+    /// `1..=n`. Returns the public key along with the key share of each holder.
+    /// A random number generator has to be provided.
+    pub fn dealer_rng<R: rand::Rng>(
+        &self,
+        n: u8,
+        rng: &mut R,
+    ) -> Result<(ElGamalPublicKey, Vec<ElGamalKeyShare>), &'static str> {
+        if self.0 == 0 || n < self.0 {
+            return Err("A threshold of at least 1 and at most n is required");
+        }
+
+        #[cfg_attr(not(feature = "zeroize_memory"), allow(unused_mut))]
+        let mut secret = scalar::random_scalar(rng);
+        let shares: Vec<ElGamalKeyShare> = scalar::deal(secret, self.0, n, rng)
+            .into_iter()
+            .zip(1..=n)
+            .map(|(share, index)| ElGamalKeyShare { index, share })
+            .collect();
+        let public = ElGamalPublicKey {
+            public_key: RistrettoPoint::mul_base(&secret),
+            verification_keys: shares
+                .iter()
+                .map(|s| RistrettoPoint::mul_base(&s.share))
+                .collect(),
+        };
+
+        #[cfg(feature = "zeroize_memory")]
+        {
+            secret.zeroize();
+        }
+
+        Ok((public, shares))
+    }
+
+    /// Same as `dealer_rng`, using `rand::thread_rng`.
+    #[cfg(feature = "std")]
+    pub fn dealer(&self, n: u8) -> Result<(ElGamalPublicKey, Vec<ElGamalKeyShare>), &'static str> {
+        let mut rng = rand::thread_rng();
+        self.dealer_rng(n, &mut rng)
+    }
+
+    /// Encrypts the group element `message` with the public key of the group.
+    /// A random number generator has to be provided.
+    pub fn encrypt_rng<R: rand::Rng>(
+        public: &ElGamalPublicKey,
+        message: &RistrettoPoint,
+        rng: &mut R,
+    ) -> Ciphertext {
+        #[cfg_attr(not(feature = "zeroize_memory"), allow(unused_mut))]
+        let mut r = scalar::random_scalar(rng);
+        let ciphertext = Ciphertext {
+            c1: RistrettoPoint::mul_base(&r),
+            c2: message + public.public_key * r,
+        };
+
+        #[cfg(feature = "zeroize_memory")]
+        {
+            r.zeroize();
+        }
+
+        ciphertext
+    }
+
+    /// Same as `encrypt_rng`, using `rand::thread_rng`.
+    #[cfg(feature = "std")]
+    pub fn encrypt(public: &ElGamalPublicKey, message: &RistrettoPoint) -> Ciphertext {
+        let mut rng = rand::thread_rng();
+        Self::encrypt_rng(public, message, &mut rng)
+    }
+
+    /// Run by each holder: returns its partial decryption of `ciphertext` with a DLEQ proof.
+    /// A random number generator has to be provided.
+    pub fn partial_decrypt_rng<R: rand::Rng>(
+        &self,
+        share: &ElGamalKeyShare,
+        ciphertext: &Ciphertext,
+        rng: &mut R,
+    ) -> PartialDecryption {
+        let c1 = &ciphertext.c1;
+        let d = c1 * share.share;
+        #[cfg_attr(not(feature = "zeroize_memory"), allow(unused_mut))]
+        let mut w = scalar::random_scalar(rng);
+        let challenge = dleq_challenge(
+            &RistrettoPoint::mul_base(&share.share),
+            c1,
+            &d,
+            &RistrettoPoint::mul_base(&w),
+            &(c1 * w),
+        );
+        let partial = PartialDecryption {
+            index: share.index,
+            d,
+            challenge,
+            response: w + challenge * share.share,
+        };
+
+        #[cfg(feature = "zeroize_memory")]
+        {
+            w.zeroize();
+        }
+
+        partial
+    }
+
+    /// Same as `partial_decrypt_rng`, using `rand::thread_rng`.
+    #[cfg(feature = "std")]
+    pub fn partial_decrypt(
+        &self,
+        share: &ElGamalKeyShare,
+        ciphertext: &Ciphertext,
+    ) -> PartialDecryption {
+        let mut rng = rand::thread_rng();
+        self.partial_decrypt_rng(share, ciphertext, &mut rng)
+    }
+
+    /// Checks the DLEQ proof of a partial decryption of `ciphertext` against the verification key
+    /// of its holder.
+    pub fn verify_partial(
+        &self,
+        public: &ElGamalPublicKey,
+        ciphertext: &Ciphertext,
+        partial: &PartialDecryption,
+    ) -> Result<(), &'static str> {
+        let v = partial
+            .index
+            .checked_sub(1)
+            .and_then(|i| public.verification_keys.get(i as usize))
+            .ok_or("Unknown holder")?;
+        let c1 = &ciphertext.c1;
+        let a = RistrettoPoint::mul_base(&partial.response) - v * partial.challenge;
+        let b = c1 * partial.response - partial.d * partial.challenge;
+        if dleq_challenge(v, c1, &partial.d, &a, &b) != partial.challenge {
+            return Err("Invalid partial decryption proof");
+        }
+        Ok(())
+    }
+
+    /// Checks the partial decryptions and combines the first `k` of them with Lagrange coefficients
+    /// in the exponent to decrypt `ciphertext`. Returns an `Err` if there are fewer than `k`
+    /// partial decryptions from distinct holders, or if any proof is invalid.
+    pub fn combine(
+        &self,
+        public: &ElGamalPublicKey,
+        ciphertext: &Ciphertext,
+        partials: &[PartialDecryption],
+    ) -> Result<RistrettoPoint, &'static str> {
+        let mut holders = HashSet::new();
+        if self.0 == 0
+            || partials.len() < self.0 as usize
+            || !partials.iter().all(|p| holders.insert(p.index))
+        {
+            return Err("At least k partial decryptions from distinct holders are required");
+        }
+        for partial in partials {
+            self.verify_partial(public, ciphertext, partial)?;
+        }
+
+        let partials = &partials[..self.0 as usize];
+        let x_samples: Vec<Scalar> = partials.iter().map(|p| Scalar::from(p.index)).collect();
+        let weights = scalar::lagrange_coefficients(&x_samples, Scalar::ZERO);
+        let mask: RistrettoPoint = weights
+            .iter()
+            .zip(partials.iter())
+            .map(|(w, p)| p.d * w)
+            .sum();
+        Ok(ciphertext.c2 - mask)
+    }
+
+    /// Decrypts `ciphertext` in-process with the first `k` key shares, as each holder and the
+    /// combiner would in the protocol.
+    /// A random number generator has to be provided.
+    pub fn decrypt_rng<R: rand::Rng>(
+        &self,
+        shares: &[ElGamalKeyShare],
+        ciphertext: &Ciphertext,
+        public: &ElGamalPublicKey,
+        rng: &mut R,
+    ) -> Result<RistrettoPoint, &'static str> {
+        let partials: Vec<PartialDecryption> = shares
+            .iter()
+            .take(self.0 as usize)
+            .map(|share| self.partial_decrypt_rng(share, ciphertext, rng))
+            .collect();
+        self.combine(public, ciphertext, &partials)
+    }
+
+    /// Same as `decrypt_rng`, using `rand::thread_rng`.
+    #[cfg(feature = "std")]
+    pub fn decrypt(
+        &self,
+        shares: &[ElGamalKeyShare],
+        ciphertext: &Ciphertext,
+        public: &ElGamalPublicKey,
+    ) -> Result<RistrettoPoint, &'static str> {
+        let mut rng = rand::thread_rng();
+        self.decrypt_rng(shares, ciphertext, public, &mut rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Ciphertext, ElGamal, ElGamalKeyShare, PartialDecryption};
+    use alloc::vec::Vec;
+    use core::convert::TryFrom;
+    use curve25519_dalek::ristretto::RistrettoPoint;
+    use curve25519_dalek::scalar::Scalar;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    use rstest::rstest;
+
+    fn message(byte: u8) -> RistrettoPoint {
+        RistrettoPoint::from_uniform_bytes(&[byte; 64])
+    }
+
+    #[rstest]
+    #[case(1, 1, vec![0])]
+    #[case(2, 3, vec![2, 0])]
+    #[case(3, 5, vec![4, 1, 3])]
+    #[case(5, 10, vec![9, 0, 2, 4, 6, 7])]
+    fn any_k_holders_decrypt(#[case] k: u8, #[case] n: u8, #[case] holders: Vec<usize>) {
+        let elgamal = ElGamal(k);
+        let mut rng = ChaCha8Rng::from_seed([0x49; 32]);
+        let (public, shares) = elgamal.dealer_rng(n, &mut rng).unwrap();
+        let ciphertext = ElGamal::encrypt_rng(&public, &message(k), &mut rng);
+
+        let partials: Vec<PartialDecryption> = holders
+            .iter()
+            .map(|&i| elgamal.partial_decrypt_rng(&shares[i], &ciphertext, &mut rng))
+            .collect();
+        assert!(partials
+            .iter()
+            .all(|p| elgamal.verify_partial(&public, &ciphertext, p).is_ok()));
+        assert_eq!(
+            elgamal.combine(&public, &ciphertext, &partials).unwrap(),
+            message(k)
+        );
+
+        let decrypted = elgamal
+            .decrypt_rng(&shares, &ciphertext, &public, &mut rng)
+            .unwrap();
+        assert_eq!(decrypted, message(k));
+    }
+
+    #[test]
+    fn invalid_partial_decryptions_err() {
+        let elgamal = ElGamal(2);
+        let mut rng = ChaCha8Rng::from_seed([0x4a; 32]);
+        let (public, shares) = elgamal.dealer_rng(3, &mut rng).unwrap();
+        let ciphertext = ElGamal::encrypt_rng(&public, &message(1), &mut rng);
+        let mut partials: Vec<PartialDecryption> = shares
+            .iter()
+            .map(|s| elgamal.partial_decrypt_rng(s, &ciphertext, &mut rng))
+            .collect();
+
+        // Not enough holders
+        assert!(elgamal
+            .combine(&public, &ciphertext, &partials[..1])
+            .is_err());
+        // Duplicated holder
+        let duplicated = [partials[0].clone(), partials[0].clone()];
+        assert!(elgamal.combine(&public, &ciphertext, &duplicated).is_err());
+
+        // A holder lies about its partial decryption
+        partials[1].d += RistrettoPoint::mul_base(&Scalar::ONE);
+        assert!(elgamal
+            .verify_partial(&public, &ciphertext, &partials[1])
+            .is_err());
+        assert!(elgamal.combine(&public, &ciphertext, &partials).is_err());
+        // A holder uses the share of another
+        let mut stolen = elgamal.partial_decrypt_rng(&shares[0], &ciphertext, &mut rng);
+        stolen.index = 3;
+        assert!(elgamal
+            .verify_partial(&public, &ciphertext, &stolen)
+            .is_err());
+        // A proof for another ciphertext
+        let other = ElGamal::encrypt_rng(&public, &message(1), &mut rng);
+        assert!(elgamal
+            .verify_partial(&public, &other, &partials[0])
+            .is_err());
+
+        assert!(ElGamal(4).dealer_rng(3, &mut rng).is_err());
+    }
+
+    #[test]
+    fn serialization_roundtrip() {
+        let elgamal = ElGamal(2);
+        let mut rng = ChaCha8Rng::from_seed([0x4b; 32]);
+        let (public, shares) = elgamal.dealer_rng(2, &mut rng).unwrap();
+        let shares: Vec<ElGamalKeyShare> = shares
+            .iter()
+            .map(|s| ElGamalKeyShare::try_from(Vec::from(s).as_slice()).unwrap())
+            .collect();
+        let ciphertext = ElGamal::encrypt_rng(&public, &message(7), &mut rng);
+        let ciphertext = Ciphertext::try_from(Vec::from(&ciphertext).as_slice()).unwrap();
+        let partials: Vec<PartialDecryption> = shares
+            .iter()
+            .map(|s| {
+                let partial = elgamal.partial_decrypt_rng(s, &ciphertext, &mut rng);
+                PartialDecryption::try_from(Vec::from(&partial).as_slice()).unwrap()
+            })
+            .collect();
+        assert_eq!(
+            elgamal.combine(&public, &ciphertext, &partials).unwrap(),
+            message(7)
+        );
+
+        assert!(Ciphertext::try_from(&[0u8; 64][..]).is_err());
+        assert!(ElGamalKeyShare::try_from(&[0xffu8; 33][..]).is_err());
+        assert!(PartialDecryption::try_from(&[1u8; 96][..]).is_err());
+    }
+}
@@ -0,0 +1,730 @@
+//! FROST threshold signing (RFC 9591) with the FROST(Ed25519, SHA-512) ciphersuite.
+//!
+//! A dealer splits an Ed25519 signing key with Shamir's secret sharing over the scalar field, and
+//! any `k` holders then sign together in two rounds without reconstructing the key. Each signer
+//! publishes a commitment to a pair of nonces, then a signature share weighted with its Lagrange
+//! coefficient. The aggregated signature is a standard Ed25519 signature of the group key.
+
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+use curve25519_dalek::scalar::Scalar;
+use curve25519_dalek::traits::IsIdentity;
+use hashbrown::HashSet;
+use sha2::{Digest, Sha512};
+
+#[cfg(feature = "zeroize_memory")]
+use zeroize::Zeroize;
+
+use super::scalar;
+
+/// The context string of the FROST(Ed25519, SHA-512) ciphersuite.
+const CONTEXT_STRING: &[u8] = b"FROST-ED25519-SHA512-v1";
+
+/// Length in bytes of a serialized group element or scalar.
+const ELEMENT_LENGTH: usize = 32;
+
+/// Length in bytes of an Ed25519 signature.
+pub const SIGNATURE_LENGTH: usize = 64;
+
+/// Hashes the concatenation of `parts` with SHA-512, prefixed with the context string and `label`
+/// unless it is empty.
+fn hash(label: &[u8], parts: &[&[u8]]) -> [u8; 64] {
+    let mut hasher = Sha512::new();
+    if !label.is_empty() {
+        hasher.update(CONTEXT_STRING);
+        hasher.update(label);
+    }
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+/// Hashes to a scalar, as `H1`, `H2` (with an empty label) and `H3` of the ciphersuite.
+fn hash_to_scalar(label: &[u8], parts: &[&[u8]]) -> Scalar {
+    Scalar::from_bytes_mod_order_wide(&hash(label, parts))
+}
+
+fn serialize_element(element: &EdwardsPoint) -> [u8; ELEMENT_LENGTH] {
+    element.compress().to_bytes()
+}
+
+/// Deserializes a canonically encoded element of the prime order subgroup, other than the identity.
+fn deserialize_element(bytes: &[u8]) -> Result<EdwardsPoint, &'static str> {
+    let bytes: [u8; ELEMENT_LENGTH] = bytes
+        .try_into()
+        .map_err(|_| "A group element must be 32 bytes long")?;
+    match CompressedEdwardsY(bytes).decompress() {
+        Some(element)
+            if element.compress().to_bytes() == bytes
+                && !element.is_identity()
+                && element.is_torsion_free() =>
+        {
+            Ok(element)
+        }
+        _ => Err("Invalid group element"),
+    }
+}
+
+/// Deserializes a canonically encoded scalar.
+fn deserialize_scalar(bytes: &[u8]) -> Result<Scalar, &'static str> {
+    let bytes: [u8; ELEMENT_LENGTH] = bytes
+        .try_into()
+        .map_err(|_| "A scalar must be 32 bytes long")?;
+    Option::from(Scalar::from_canonical_bytes(bytes)).ok_or("Invalid scalar")
+}
+
+/// The key share of the signer at `identifier`, along with the public keys.
+///
+/// Can be serialized to and from a byte array as
+/// `[identifier, signing share, verifying share, group key]`, where scalars and group elements are
+/// 32 bytes long.
+#[derive(Clone)]
+#[cfg_attr(feature = "zeroize_memory", derive(Zeroize))]
+#[cfg_attr(feature = "zeroize_memory", zeroize(drop))]
+pub struct KeyPackage {
+    /// The identifier of the signer, its x coordinate.
+    pub identifier: u8,
+    /// The share of the signing key.
+    pub signing_share: Scalar,
+    /// The public key of the share.
+    pub verifying_share: EdwardsPoint,
+    /// The public key of the group.
+    pub group_key: EdwardsPoint,
+}
+
+impl From<&KeyPackage> for Vec<u8> {
+    fn from(key: &KeyPackage) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(1 + 3 * ELEMENT_LENGTH);
+        bytes.push(key.identifier);
+        bytes.extend_from_slice(key.signing_share.as_bytes());
+        bytes.extend_from_slice(&serialize_element(&key.verifying_share));
+        bytes.extend_from_slice(&serialize_element(&key.group_key));
+        bytes
+    }
+}
+
+impl TryFrom<&[u8]> for KeyPackage {
+    type Error = &'static str;
+
+    fn try_from(s: &[u8]) -> Result<KeyPackage, Self::Error> {
+        if s.len() != 1 + 3 * ELEMENT_LENGTH || s[0] == 0 {
+            return Err("Invalid key package");
+        }
+        let key = KeyPackage {
+            identifier: s[0],
+            signing_share: deserialize_scalar(&s[1..33])?,
+            verifying_share: deserialize_element(&s[33..65])?,
+            group_key: deserialize_element(&s[65..97])?,
+        };
+        if EdwardsPoint::mul_base(&key.signing_share) != key.verifying_share {
+            return Err("The verifying share does not match the signing share");
+        }
+        Ok(key)
+    }
+}
+
+/// The public keys of the signers, used to verify signature shares, and of the group.
+#[derive(Clone)]
+pub struct PublicKeyPackage {
+    /// The verifying share of the signer at `x`, at position `x - 1`.
+    pub verifying_shares: Vec<EdwardsPoint>,
+    /// The public key of the group.
+    pub group_key: EdwardsPoint,
+}
+
+impl PublicKeyPackage {
+    /// Returns the Ed25519 public key of the group, which verifies the aggregated signatures.
+    pub fn verifying_key(&self) -> [u8; 32] {
+        serialize_element(&self.group_key)
+    }
+}
+
+/// The secret nonces of a signer for a single signature. They are consumed by signing, and must
+/// never be reused.
+#[cfg_attr(feature = "zeroize_memory", derive(Zeroize))]
+#[cfg_attr(feature = "zeroize_memory", zeroize(drop))]
+pub struct SigningNonces {
+    hiding: Scalar,
+    binding: Scalar,
+}
+
+/// The public commitments to the nonces of the signer at `identifier`, sent in the first round.
+///
+/// Can be serialized to and from a byte array as `[identifier, hiding, binding]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SigningCommitments {
+    /// The identifier of the signer.
+    pub identifier: u8,
+    /// The commitment to the hiding nonce.
+    pub hiding: EdwardsPoint,
+    /// The commitment to the binding nonce.
+    pub binding: EdwardsPoint,
+}
+
+impl From<&SigningCommitments> for Vec<u8> {
+    fn from(c: &SigningCommitments) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(1 + 2 * ELEMENT_LENGTH);
+        bytes.push(c.identifier);
+        bytes.extend_from_slice(&serialize_element(&c.hiding));
+        bytes.extend_from_slice(&serialize_element(&c.binding));
+        bytes
+    }
+}
+
+impl TryFrom<&[u8]> for SigningCommitments {
+    type Error = &'static str;
+
+    fn try_from(s: &[u8]) -> Result<SigningCommitments, Self::Error> {
+        if s.len() != 1 + 2 * ELEMENT_LENGTH || s[0] == 0 {
+            return Err("Invalid signing commitments");
+        }
+        Ok(SigningCommitments {
+            identifier: s[0],
+            hiding: deserialize_element(&s[1..33])?,
+            binding: deserialize_element(&s[33..65])?,
+        })
+    }
+}
+
+/// The signature share of the signer at `identifier`, sent in the second round.
+///
+/// Can be serialized to and from a byte array as `[identifier, z]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignatureShare {
+    /// The identifier of the signer.
+    pub identifier: u8,
+    /// The share of the response scalar.
+    pub z: Scalar,
+}
+
+impl From<&SignatureShare> for Vec<u8> {
+    fn from(s: &SignatureShare) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(1 + ELEMENT_LENGTH);
+        bytes.push(s.identifier);
+        bytes.extend_from_slice(s.z.as_bytes());
+        bytes
+    }
+}
+
+impl TryFrom<&[u8]> for SignatureShare {
+    type Error = &'static str;
+
+    fn try_from(s: &[u8]) -> Result<SignatureShare, Self::Error> {
+        if s.len() != 1 + ELEMENT_LENGTH || s[0] == 0 {
+            return Err("Invalid signature share");
+        }
+        Ok(SignatureShare {
+            identifier: s[0],
+            z: deserialize_scalar(&s[1..])?,
+        })
+    }
+}
+
+/// The values of a signing session derived from the message and the commitments of all signers.
+struct Session {
+    /// The identifiers of the signers, as scalars, in ascending order.
+    identifiers: Vec<Scalar>,
+    /// The binding factor of each signer.
+    binding_factors: Vec<Scalar>,
+    /// The commitment share of each signer, `hiding + binding_factor * binding`.
+    commitment_shares: Vec<EdwardsPoint>,
+    /// The group commitment `R`.
+    group_commitment: EdwardsPoint,
+    /// The challenge `c` of the signature.
+    challenge: Scalar,
+}
+
+impl Session {
+    /// Computes the binding factors, the group commitment and the challenge of the signature of
+    /// `message` by the signers with `commitments`, sorted by identifier.
+    fn new(
+        threshold: u8,
+        group_key: &EdwardsPoint,
+        message: &[u8],
+        commitments: &[SigningCommitments],
+    ) -> Result<Session, &'static str> {
+        let mut identifiers = HashSet::new();
+        if commitments.len() < threshold as usize
+            || !commitments
+                .iter()
+                .all(|c| c.identifier != 0 && identifiers.insert(c.identifier))
+        {
+            return Err("At least k commitments from distinct signers are required");
+        }
+        let mut commitments: Vec<&SigningCommitments> = commitments.iter().collect();
+        commitments.sort_by_key(|c| c.identifier);
+        let identifiers: Vec<Scalar> = commitments
+            .iter()
+            .map(|c| Scalar::from(c.identifier))
+            .collect();
+
+        let encoded_commitments: Vec<u8> = commitments
+            .iter()
+            .zip(identifiers.iter())
+            .flat_map(|(c, id)| {
+                let mut bytes = id.to_bytes().to_vec();
+                bytes.extend_from_slice(&serialize_element(&c.hiding));
+                bytes.extend_from_slice(&serialize_element(&c.binding));
+                bytes
+            })
+            .collect();
+        let message_hash = hash(b"msg", &[message]);
+        let commitments_hash = hash(b"com", &[&encoded_commitments]);
+        let group_key_bytes = serialize_element(group_key);
+
+        let binding_factors: Vec<Scalar> = identifiers
+            .iter()
+            .map(|id| {
+                hash_to_scalar(
+                    b"rho",
+                    &[
+                        &group_key_bytes,
+                        &message_hash,
+                        &commitments_hash,
+                        id.as_bytes(),
+                    ],
+                )
+            })
+            .collect();
+        let commitment_shares: Vec<EdwardsPoint> = commitments
+            .iter()
+            .zip(binding_factors.iter())
+            .map(|(c, rho)| c.hiding + c.binding * rho)
+            .collect();
+        let group_commitment: EdwardsPoint = commitment_shares.iter().sum();
+        let challenge = hash_to_scalar(
+            b"",
+            &[
+                &serialize_element(&group_commitment),
+                &group_key_bytes,
+                message,
+            ],
+        );
+
+        Ok(Session {
+            identifiers,
+            binding_factors,
+            commitment_shares,
+            group_commitment,
+            challenge,
+        })
+    }
+
+    /// Returns the index of the signer at `identifier`, its binding factor and Lagrange coefficient.
+    fn signer(&self, identifier: u8) -> Result<(usize, Scalar, Scalar), &'static str> {
+        let index = self
+            .identifiers
+            .iter()
+            .position(|id| *id == Scalar::from(identifier))
+            .ok_or("The signer must be one of the committed signers")?;
+        let lambda =
+            scalar::lagrange_coefficients(&self.identifiers, Scalar::ZERO).swap_remove(index);
+        Ok((index, self.binding_factors[index], lambda))
+    }
+}
+
+/// Struct which implements FROST threshold signing with the FROST(Ed25519, SHA-512) ciphersuite,
+/// where the tuple parameter is the threshold `k`.
+///
+/// Usage example:
+/// ```
+/// # use ssskit::Frost;
+/// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+/// use ed25519_dalek::{Signature, SigningKey, Verifier};
+///
+/// let frost = Frost(2);
+/// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+/// let signing_key = SigningKey::from_bytes(&[7; 32]);
+/// let (public, keys) = frost.dealer_rng(&signing_key.to_bytes(), 3, &mut rng).unwrap();
+/// assert_eq!(public.verifying_key(), signing_key.verifying_key().to_bytes());
+///
+/// // Any 2 signers produce a standard Ed25519 signature
+/// let signature = frost.threshold_sign_rng(b"hello", &keys[1..], &public, &mut rng).unwrap();
+/// let signature = Signature::from_bytes(&signature);
+/// assert!(signing_key.verifying_key().verify(b"hello", &signature).is_ok());
+/// ```
+pub struct Frost(pub u8);
+
+impl Frost {
+    /// Splits the Ed25519 signing key `seed` with threshold `k` among `n` signers with identifiers
+    /// `1..=n`, and returns the public keys along with the key package of each signer.
+    /// A random number generator has to be provided.
+    pub fn dealer_rng<R: rand::Rng>(
+        &self,
+        seed: &[u8; 32],
+        n: u8,
+        rng: &mut R,
+    ) -> Result<(PublicKeyPackage, Vec<KeyPackage>), &'static str> {
+        // Ed25519 key expansion (RFC 8032)
+        #[cfg_attr(not(feature = "zeroize_memory"), allow(unused_mut))]
+        let mut expanded = hash(b"", &[seed]);
+        let mut secret_bytes = [0u8; 32];
+        secret_bytes.copy_from_slice(&expanded[..32]);
+        secret_bytes[0] &= 248;
+        secret_bytes[31] &= 127;
+        secret_bytes[31] |= 64;
+        #[cfg_attr(not(feature = "zeroize_memory"), allow(unused_mut))]
+        let mut secret = Scalar::from_bytes_mod_order(secret_bytes);
+
+        let keys = self.deal(secret, n, rng);
+
+        #[cfg(feature = "zeroize_memory")]
+        {
+            expanded.zeroize();
+            secret_bytes.zeroize();
+            secret.zeroize();
+        }
+
+        keys
+    }
+
+    /// Same as `dealer_rng`, using `rand::thread_rng`.
+    #[cfg(feature = "std")]
+    pub fn dealer(
+        &self,
+        seed: &[u8; 32],
+        n: u8,
+    ) -> Result<(PublicKeyPackage, Vec<KeyPackage>), &'static str> {
+        let mut rng = rand::thread_rng();
+        self.dealer_rng(seed, n, &mut rng)
+    }
+
+    /// Shares the signing key scalar `secret`.
+    fn deal<R: rand::Rng>(
+        &self,
+        secret: Scalar,
+        n: u8,
+        rng: &mut R,
+    ) -> Result<(PublicKeyPackage, Vec<KeyPackage>), &'static str> {
+        if self.0 == 0 || n < self.0 {
+            return Err("A threshold of at least 1 and at most n is required");
+        }
+
+        let shares = scalar::deal(secret, self.0, n, rng);
+        let group_key = EdwardsPoint::mul_base(&secret);
+        let keys: Vec<KeyPackage> = shares
+            .iter()
+            .zip(1..=n)
+            .map(|(share, identifier)| KeyPackage {
+                identifier,
+                signing_share: *share,
+                verifying_share: EdwardsPoint::mul_base(share),
+                group_key,
+            })
+            .collect();
+        let public = PublicKeyPackage {
+            verifying_shares: keys.iter().map(|k| k.verifying_share).collect(),
+            group_key,
+        };
+
+        #[cfg(feature = "zeroize_memory")]
+        {
+            let mut shares = shares;
+            shares.zeroize();
+        }
+
+        Ok((public, keys))
+    }
+
+    /// First round of signing, run by each signer: returns fresh nonces, to be kept secret for the
+    /// second round, and their commitments, to be sent to the other signers.
+    /// A random number generator has to be provided.
+    pub fn commit_rng<R: rand::Rng>(
+        &self,
+        key: &KeyPackage,
+        rng: &mut R,
+    ) -> (SigningNonces, SigningCommitments) {
+        let mut nonce = || {
+            let mut random = [0u8; 32];
+            rng.fill(&mut random[..]);
+            let nonce = hash_to_scalar(b"nonce", &[&random, key.signing_share.as_bytes()]);
+
+            #[cfg(feature = "zeroize_memory")]
+            random.zeroize();
+
+            nonce
+        };
+        let nonces = SigningNonces {
+            hiding: nonce(),
+            binding: nonce(),
+        };
+        let commitments = SigningCommitments {
+            identifier: key.identifier,
+            hiding: EdwardsPoint::mul_base(&nonces.hiding),
+            binding: EdwardsPoint::mul_base(&nonces.binding),
+        };
+        (nonces, commitments)
+    }
+
+    /// Same as `commit_rng`, using `rand::thread_rng`.
+    #[cfg(feature = "std")]
+    pub fn commit(&self, key: &KeyPackage) -> (SigningNonces, SigningCommitments) {
+        let mut rng = rand::thread_rng();
+        self.commit_rng(key, &mut rng)
+    }
+
+    /// Second round of signing, run by each signer: returns its signature share of `message`,
+    /// given its nonces and the commitments of at least `k` signers, including itself.
+    pub fn sign(
+        &self,
+        message: &[u8],
+        key: &KeyPackage,
+        nonces: SigningNonces,
+        commitments: &[SigningCommitments],
+    ) -> Result<SignatureShare, &'static str> {
+        let session = Session::new(self.0, &key.group_key, message, commitments)?;
+        let (_, binding_factor, lambda) = session.signer(key.identifier)?;
+        let z = nonces.hiding
+            + nonces.binding * binding_factor
+            + lambda * key.signing_share * session.challenge;
+        Ok(SignatureShare {
+            identifier: key.identifier,
+            z,
+        })
+    }
+
+    /// Checks the signature share of `message` of a signer against its verifying share.
+    pub fn verify_share(
+        &self,
+        message: &[u8],
+        share: &SignatureShare,
+        commitments: &[SigningCommitments],
+        public: &PublicKeyPackage,
+    ) -> Result<(), &'static str> {
+        let session = Session::new(self.0, &public.group_key, message, commitments)?;
+        self.verify_in_session(&session, share, public)
+    }
+
+    fn verify_in_session(
+        &self,
+        session: &Session,
+        share: &SignatureShare,
+        public: &PublicKeyPackage,
+    ) -> Result<(), &'static str> {
+        let (index, _, lambda) = session.signer(share.identifier)?;
+        let verifying_share = public
+            .verifying_shares
+            .get(share.identifier as usize - 1)
+            .ok_or("Unknown signer")?;
+        let expected =
+            session.commitment_shares[index] + verifying_share * (session.challenge * lambda);
+        if EdwardsPoint::mul_base(&share.z) != expected {
+            return Err("Invalid signature share");
+        }
+        Ok(())
+    }
+
+    /// Aggregates the signature shares of `message` of all the committed signers into an Ed25519
+    /// signature, after checking each of them. Returns an `Err` if any share is missing or invalid.
+    pub fn aggregate(
+        &self,
+        message: &[u8],
+        commitments: &[SigningCommitments],
+        shares: &[SignatureShare],
+        public: &PublicKeyPackage,
+    ) -> Result<[u8; SIGNATURE_LENGTH], &'static str> {
+        let session = Session::new(self.0, &public.group_key, message, commitments)?;
+        let mut signers = HashSet::new();
+        if shares.len() != commitments.len() || !shares.iter().all(|s| signers.insert(s.identifier))
+        {
+            return Err("Exactly one signature share per committed signer is required");
+        }
+        for share in shares {
+            self.verify_in_session(&session, share, public)?;
+        }
+
+        let z: Scalar = shares.iter().map(|s| s.z).sum();
+        let mut signature = [0u8; SIGNATURE_LENGTH];
+        signature[..32].copy_from_slice(&serialize_element(&session.group_commitment));
+        signature[32..].copy_from_slice(z.as_bytes());
+        Ok(signature)
+    }
+
+    /// Signs `message` in-process with the first `k` key packages, as each signer and the
+    /// aggregator would in the protocol.
+    /// A random number generator has to be provided.
+    pub fn threshold_sign_rng<R: rand::Rng>(
+        &self,
+        message: &[u8],
+        keys: &[KeyPackage],
+        public: &PublicKeyPackage,
+        rng: &mut R,
+    ) -> Result<[u8; SIGNATURE_LENGTH], &'static str> {
+        let keys = keys.get(..self.0 as usize).ok_or("Not enough signers")?;
+        let (nonces, commitments): (Vec<SigningNonces>, Vec<SigningCommitments>) =
+            keys.iter().map(|key| self.commit_rng(key, rng)).unzip();
+        let shares = keys
+            .iter()
+            .zip(nonces)
+            .map(|(key, nonces)| self.sign(message, key, nonces, &commitments))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.aggregate(message, &commitments, &shares, public)
+    }
+
+    /// Same as `threshold_sign_rng`, using `rand::thread_rng`.
+    #[cfg(feature = "std")]
+    pub fn threshold_sign(
+        &self,
+        message: &[u8],
+        keys: &[KeyPackage],
+        public: &PublicKeyPackage,
+    ) -> Result<[u8; SIGNATURE_LENGTH], &'static str> {
+        let mut rng = rand::thread_rng();
+        self.threshold_sign_rng(message, keys, public, &mut rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Frost, KeyPackage, SignatureShare, SigningCommitments, SigningNonces};
+    use alloc::vec::Vec;
+    use core::convert::TryFrom;
+    use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(1, 1, vec![0])]
+    #[case(2, 3, vec![2, 0])]
+    #[case(3, 5, vec![4, 1, 3])]
+    #[case(4, 7, vec![0, 2, 4, 5, 6])]
+    fn signatures_verify_with_ed25519(#[case] k: u8, #[case] n: u8, #[case] signers: Vec<usize>) {
+        let frost = Frost(k);
+        let mut rng = ChaCha8Rng::from_seed([0x48; 32]);
+        let signing_key = SigningKey::from_bytes(&[k; 32]);
+        let (public, keys) = frost
+            .dealer_rng(&signing_key.to_bytes(), n, &mut rng)
+            .unwrap();
+        let verifying_key = VerifyingKey::from_bytes(&public.verifying_key()).unwrap();
+        assert_eq!(verifying_key, signing_key.verifying_key());
+
+        let message = b"threshold signatures without the key";
+        let signers: Vec<&KeyPackage> = signers.iter().map(|&i| &keys[i]).collect();
+        let (nonces, commitments): (Vec<SigningNonces>, Vec<SigningCommitments>) = signers
+            .iter()
+            .map(|key| frost.commit_rng(key, &mut rng))
+            .unzip();
+        let shares: Vec<SignatureShare> = signers
+            .iter()
+            .zip(nonces)
+            .map(|(key, nonces)| frost.sign(message, key, nonces, &commitments).unwrap())
+            .collect();
+        let signature = frost
+            .aggregate(message, &commitments, &shares, &public)
+            .unwrap();
+
+        let signature = Signature::from_bytes(&signature);
+        assert!(verifying_key.verify_strict(message, &signature).is_ok());
+        assert!(verifying_key
+            .verify(b"another message", &signature)
+            .is_err());
+        // Ed25519 signatures are deterministic, FROST ones are randomized by the nonces
+        assert_ne!(signature, signing_key.sign(message));
+    }
+
+    #[test]
+    fn invalid_share_is_identified() {
+        let frost = Frost(2);
+        let mut rng = ChaCha8Rng::from_seed([0x49; 32]);
+        let (public, keys) = frost.dealer_rng(&[1; 32], 3, &mut rng).unwrap();
+        let (nonces, commitments): (Vec<SigningNonces>, Vec<SigningCommitments>) = keys[..2]
+            .iter()
+            .map(|key| frost.commit_rng(key, &mut rng))
+            .unzip();
+        let mut shares: Vec<SignatureShare> = keys[..2]
+            .iter()
+            .zip(nonces)
+            .map(|(key, nonces)| frost.sign(b"msg", key, nonces, &commitments).unwrap())
+            .collect();
+        assert!(frost
+            .verify_share(b"msg", &shares[1], &commitments, &public)
+            .is_ok());
+
+        shares[1].z += curve25519_dalek::scalar::Scalar::ONE;
+        assert!(frost
+            .verify_share(b"msg", &shares[1], &commitments, &public)
+            .is_err());
+        assert!(frost
+            .verify_share(b"msg", &shares[0], &commitments, &public)
+            .is_ok());
+        assert!(frost
+            .aggregate(b"msg", &commitments, &shares, &public)
+            .is_err());
+        assert!(frost
+            .aggregate(b"msg", &commitments, &shares[..1], &public)
+            .is_err());
+    }
+
+    #[test]
+    fn invalid_parameters_err() {
+        let frost = Frost(3);
+        let mut rng = ChaCha8Rng::from_seed([0x49; 32]);
+        assert!(frost.dealer_rng(&[1; 32], 2, &mut rng).is_err());
+        assert!(Frost(0).dealer_rng(&[1; 32], 2, &mut rng).is_err());
+
+        let (public, keys) = frost.dealer_rng(&[1; 32], 4, &mut rng).unwrap();
+        assert!(frost
+            .threshold_sign_rng(b"msg", &keys[..2], &public, &mut rng)
+            .is_err());
+
+        let (nonces, commitments): (Vec<SigningNonces>, Vec<SigningCommitments>) = keys[..2]
+            .iter()
+            .map(|key| frost.commit_rng(key, &mut rng))
+            .unzip();
+        // Not enough commitments
+        let nonce = nonces.into_iter().next().unwrap();
+        assert!(frost.sign(b"msg", &keys[0], nonce, &commitments).is_err());
+        // Signer not among the committed ones
+        let (nonce, commitment) = frost.commit_rng(&keys[2], &mut rng);
+        let mut others = commitments.clone();
+        others.push(commitment);
+        assert!(frost.sign(b"msg", &keys[3], nonce, &others).is_err());
+    }
+
+    #[test]
+    fn serialization_roundtrip() {
+        let frost = Frost(2);
+        let mut rng = ChaCha8Rng::from_seed([0x4a; 32]);
+        let (public, keys) = frost.dealer_rng(&[9; 32], 2, &mut rng).unwrap();
+        let keys: Vec<KeyPackage> = keys
+            .iter()
+            .map(|k| KeyPackage::try_from(Vec::from(k).as_slice()).unwrap())
+            .collect();
+
+        let (nonces, commitments): (Vec<SigningNonces>, Vec<SigningCommitments>) = keys
+            .iter()
+            .map(|key| frost.commit_rng(key, &mut rng))
+            .unzip();
+        let commitments: Vec<SigningCommitments> = commitments
+            .iter()
+            .map(|c| SigningCommitments::try_from(Vec::from(c).as_slice()).unwrap())
+            .collect();
+        let shares: Vec<SignatureShare> = keys
+            .iter()
+            .zip(nonces)
+            .map(|(key, nonces)| {
+                let share = frost.sign(b"msg", key, nonces, &commitments).unwrap();
+                SignatureShare::try_from(Vec::from(&share).as_slice()).unwrap()
+            })
+            .collect();
+        let signature = frost
+            .aggregate(b"msg", &commitments, &shares, &public)
+            .unwrap();
+        let verifying_key = VerifyingKey::from_bytes(&public.verifying_key()).unwrap();
+        assert!(verifying_key
+            .verify_strict(b"msg", &Signature::from_bytes(&signature))
+            .is_ok());
+
+        // Mismatched verifying share and identity elements are rejected
+        let mut bytes = Vec::from(&keys[0]);
+        bytes[33..65].copy_from_slice(&Vec::from(&keys[1])[33..65]);
+        assert!(KeyPackage::try_from(bytes.as_slice()).is_err());
+        let mut bytes = Vec::from(&commitments[0]);
+        bytes[1..33].fill(0);
+        bytes[1] = 1;
+        assert!(SigningCommitments::try_from(bytes.as_slice()).is_err());
+        assert!(SignatureShare::try_from(&[0xffu8; 33][..]).is_err());
+    }
+}
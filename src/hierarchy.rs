@@ -0,0 +1,333 @@
+// Hierarchical secret sharing: the secret is split among groups with a group threshold, and every
+// group share is split again among the group's members with their own threshold, down to any depth.
+// A member share is tagged with the `x` coordinates of the shares it derives from at every level.
+
+use alloc::vec::Vec;
+use hashbrown::HashSet;
+
+#[cfg(feature = "zeroize_memory")]
+use zeroize::Zeroize;
+
+use super::field::GF256;
+use super::math;
+use super::share::ShareWithX;
+use super::SecretSharing;
+
+/// A node of a sharing hierarchy: either a member, who holds a share, or a group whose share is
+/// split again among its members.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Hierarchy {
+    /// A member holding a share.
+    Member,
+    /// A group whose share is recovered by any `threshold` of its `members`.
+    Group {
+        /// Minimum number of members required to recover the group share.
+        threshold: u8,
+        /// The members of the group, which may be groups themselves.
+        members: Vec<Hierarchy>,
+    },
+}
+
+impl Hierarchy {
+    /// Returns a group of `count` plain members with the given `threshold`.
+    pub fn group(threshold: u8, count: u8) -> Hierarchy {
+        Hierarchy::Group {
+            threshold,
+            members: alloc::vec![Hierarchy::Member; count as usize],
+        }
+    }
+}
+
+/// A share held by a member of a sharing hierarchy.
+#[derive(Clone)]
+#[cfg_attr(feature = "zeroize_memory", derive(Zeroize))]
+#[cfg_attr(feature = "zeroize_memory", zeroize(drop))]
+pub struct MemberShare<const POLY: u16> {
+    /// The x coordinates of the shares the member share derives from, from the top level down,
+    /// which are the 1-based indices of the member and its enclosing groups.
+    pub path: Vec<u8>,
+    /// The y coordinates of the share.
+    pub y: Vec<u8>,
+}
+
+impl<const POLY: u16> From<&MemberShare<POLY>> for Vec<u8> {
+    /// Serializes the member share as `[depth, path..., y...]`.
+    fn from(s: &MemberShare<POLY>) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(1 + s.path.len() + s.y.len());
+        bytes.push(s.path.len() as u8);
+        bytes.extend_from_slice(&s.path);
+        bytes.extend_from_slice(&s.y);
+        bytes
+    }
+}
+
+impl<const POLY: u16> core::convert::TryFrom<&[u8]> for MemberShare<POLY> {
+    type Error = &'static str;
+
+    fn try_from(s: &[u8]) -> Result<MemberShare<POLY>, Self::Error> {
+        let depth = *s.first().ok_or("A MemberShare must not be empty")? as usize;
+        if depth == 0 || s.len() <= 1 + depth {
+            return Err("A MemberShare must hold a non-empty path and share");
+        }
+        let path = s[1..=depth].to_vec();
+        if path.contains(&0) {
+            return Err("A MemberShare path must not contain a 0 x coordinate");
+        }
+        Ok(MemberShare {
+            path,
+            y: s[1 + depth..].to_vec(),
+        })
+    }
+}
+
+impl<const POLY: u16> SecretSharing<POLY> {
+    /// Given a `secret` byte slice and the top level `groups` of a hierarchy, any `k` of which
+    /// recover the secret, returns the shares of all the members of the hierarchy in depth-first
+    /// order. A random number generator has to be provided.
+    ///
+    /// Example:
+    /// ```
+    /// # use ssskit::{ Hierarchy, MemberShare, SecretSharing };
+    /// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    /// # const POLY: u16 = 0x11d_u16;
+    /// // Any 2 of 3 groups: 2 of 3 board members, 1 of 2 executives, 3 of 5 engineers
+    /// let sss = SecretSharing::<POLY>(2);
+    /// let groups = [
+    ///     Hierarchy::group(2, 3),
+    ///     Hierarchy::group(1, 2),
+    ///     Hierarchy::group(3, 5),
+    /// ];
+    /// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// let shares = sss
+    ///     .dealer_hierarchical_rng(&[1, 2, 3], &groups, &mut rng)
+    ///     .unwrap();
+    /// assert_eq!(shares.len(), 10);
+    /// // Two board members and an executive
+    /// let secret = sss
+    ///     .recover_hierarchical(&groups, [&shares[0], &shares[2], &shares[4]])
+    ///     .unwrap();
+    /// assert_eq!(secret, vec![1, 2, 3]);
+    /// // One board member and two engineers are not enough
+    /// assert!(sss
+    ///     .recover_hierarchical(&groups, [&shares[0], &shares[5], &shares[6]])
+    ///     .is_err());
+    /// ```
+    pub fn dealer_hierarchical_rng<R: rand::Rng>(
+        &self,
+        secret: &[u8],
+        groups: &[Hierarchy],
+        rng: &mut R,
+    ) -> Result<Vec<MemberShare<POLY>>, &'static str> {
+        let mut shares = Vec::new();
+        let mut path = Vec::new();
+        deal(self.0, groups, secret, &mut path, &mut shares, rng)?;
+        Ok(shares)
+    }
+
+    /// Same as `dealer_hierarchical_rng`, using `rand::thread_rng`.
+    #[cfg(feature = "std")]
+    pub fn dealer_hierarchical(
+        &self,
+        secret: &[u8],
+        groups: &[Hierarchy],
+    ) -> Result<Vec<MemberShare<POLY>>, &'static str> {
+        let mut rng = rand::thread_rng();
+        self.dealer_hierarchical_rng(secret, groups, &mut rng)
+    }
+
+    /// Given the top level `groups` of the hierarchy and an iterable collection of member shares,
+    /// recovers the original secret. Groups without enough member shares are skipped, and an `Err`
+    /// is returned if fewer than `k` groups can be recovered.
+    pub fn recover_hierarchical<'a, T>(
+        &self,
+        groups: &[Hierarchy],
+        shares: T,
+    ) -> Result<Vec<u8>, &'static str>
+    where
+        T: IntoIterator<Item = &'a MemberShare<POLY>>,
+        T::IntoIter: Iterator<Item = &'a MemberShare<POLY>>,
+    {
+        let shares: Vec<&MemberShare<POLY>> = shares.into_iter().collect();
+        let mut paths = HashSet::new();
+        if !shares.iter().all(|s| paths.insert(s.path.as_slice())) {
+            return Err("Member shares must have distinct paths");
+        }
+
+        recover(self.0, groups, &shares, 0)?.ok_or("Not enough shares to recover original secret")
+    }
+}
+
+/// Splits `secret` among the `members` of a group and recursively among their own members,
+/// pushing the member shares to `shares`.
+fn deal<const POLY: u16, R: rand::Rng>(
+    threshold: u8,
+    members: &[Hierarchy],
+    secret: &[u8],
+    path: &mut Vec<u8>,
+    shares: &mut Vec<MemberShare<POLY>>,
+    rng: &mut R,
+) -> Result<(), &'static str> {
+    if threshold == 0 || threshold as usize > members.len() || members.len() > u8::MAX as usize {
+        return Err("Group threshold must be between 1 and the number of members, at most 255");
+    }
+
+    let dealer = SecretSharing::<POLY>(threshold).dealer_rng(secret, rng);
+    for ((member, share), x) in members.iter().zip(dealer).zip(1..=u8::MAX) {
+        let y: Vec<u8> = share.y.iter().map(|y| y.0).collect();
+        path.push(x);
+        match member {
+            Hierarchy::Member => shares.push(MemberShare {
+                path: path.clone(),
+                y: y.clone(),
+            }),
+            Hierarchy::Group { threshold, members } => {
+                deal(*threshold, members, &y, path, shares, rng)?
+            }
+        }
+        path.pop();
+
+        #[cfg(feature = "zeroize_memory")]
+        {
+            let mut y = y;
+            y.zeroize();
+        }
+    }
+    Ok(())
+}
+
+/// Recovers the secret of a group from the member shares whose paths go through it, where `depth`
+/// is the level of its members. Returns `None` if fewer than `threshold` members can be recovered.
+fn recover<const POLY: u16>(
+    threshold: u8,
+    members: &[Hierarchy],
+    shares: &[&MemberShare<POLY>],
+    depth: usize,
+) -> Result<Option<Vec<u8>>, &'static str> {
+    let mut values: Vec<ShareWithX<POLY>> = Vec::new();
+    for (member, x) in members.iter().zip(1..=u8::MAX) {
+        let member_shares: Vec<&MemberShare<POLY>> = shares
+            .iter()
+            .filter(|s| s.path.get(depth) == Some(&x))
+            .copied()
+            .collect();
+        let y = match member {
+            Hierarchy::Member => member_shares
+                .iter()
+                .find(|s| s.path.len() == depth + 1)
+                .map(|s| s.y.clone()),
+            Hierarchy::Group { threshold, members } => {
+                recover(*threshold, members, &member_shares, depth + 1)?
+            }
+        };
+        if let Some(y) = y {
+            values.push(ShareWithX {
+                x: GF256(x),
+                y: y.iter().map(|y| GF256(*y)).collect(),
+            });
+        }
+    }
+
+    if values.is_empty() || values.len() < threshold as usize {
+        return Ok(None);
+    }
+    if values.iter().any(|s| s.y.len() != values[0].y.len()) {
+        return Err("All shares must have the same length");
+    }
+    values.truncate(threshold.max(1) as usize);
+    Ok(Some(math::interpolate(&values)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Hierarchy, MemberShare};
+    use crate::SecretSharing;
+    use alloc::{vec, vec::Vec};
+    use core::convert::TryFrom;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    use rstest::rstest;
+
+    const POLY: u16 = 0x11d_u16;
+
+    fn make_shares(k: u8, groups: &[Hierarchy]) -> Vec<MemberShare<POLY>> {
+        let mut rng = ChaCha8Rng::from_seed([0x38; 32]);
+        SecretSharing::<POLY>(k)
+            .dealer_hierarchical_rng(&[9, 8, 7, 6], groups, &mut rng)
+            .unwrap()
+    }
+
+    fn deep_hierarchy() -> Vec<Hierarchy> {
+        // Shares: [0, 1] in a 1 of 2 group nested with member 2 in a 2 of 2 group,
+        // then [3, 4, 5] in a 2 of 3 group and a single member 6.
+        vec![
+            Hierarchy::Group {
+                threshold: 2,
+                members: vec![Hierarchy::group(1, 2), Hierarchy::Member],
+            },
+            Hierarchy::group(2, 3),
+            Hierarchy::Member,
+        ]
+    }
+
+    #[rstest]
+    #[case(2, vec![0, 2, 3, 4], true)]
+    #[case(2, vec![1, 2, 6], true)]
+    #[case(2, vec![3, 5, 6], true)]
+    #[case(2, vec![0, 1, 3, 6], false)]
+    #[case(2, vec![0, 2, 3], false)]
+    #[case(3, vec![1, 2, 4, 5, 6], true)]
+    #[case(3, vec![1, 2, 4, 6], false)]
+    #[case(1, vec![6], true)]
+    fn deep_hierarchy_recovery(#[case] k: u8, #[case] present: Vec<usize>, #[case] recovers: bool) {
+        let groups = deep_hierarchy();
+        let shares = make_shares(k, &groups);
+        assert_eq!(shares.len(), 7);
+        assert_eq!(shares[1].path, vec![1, 1, 2]);
+        assert_eq!(shares[6].path, vec![3]);
+
+        let result = SecretSharing::<POLY>(k)
+            .recover_hierarchical(&groups, present.iter().map(|&i| &shares[i]));
+        if recovers {
+            assert_eq!(result.unwrap(), vec![9, 8, 7, 6]);
+        } else {
+            assert!(result.is_err());
+        }
+    }
+
+    #[test]
+    fn serialization_roundtrip() {
+        let groups = deep_hierarchy();
+        let shares: Vec<MemberShare<POLY>> = make_shares(2, &groups)
+            .iter()
+            .map(|s| MemberShare::try_from(Vec::from(s).as_slice()).unwrap())
+            .collect();
+        let secret = SecretSharing::<POLY>(2)
+            .recover_hierarchical(&groups, [&shares[3], &shares[4], &shares[6]])
+            .unwrap();
+        assert_eq!(secret, vec![9, 8, 7, 6]);
+
+        assert!(MemberShare::<POLY>::try_from(&[][..]).is_err());
+        assert!(MemberShare::<POLY>::try_from(&[2u8, 1, 1][..]).is_err());
+        assert!(MemberShare::<POLY>::try_from(&[1u8, 0, 1][..]).is_err());
+    }
+
+    #[test]
+    fn invalid_hierarchy_err() {
+        let mut rng = ChaCha8Rng::from_seed([0x38; 32]);
+        let sss = SecretSharing::<POLY>(2);
+        assert!(sss
+            .dealer_hierarchical_rng(&[1], &[Hierarchy::Member], &mut rng)
+            .is_err());
+        assert!(sss
+            .dealer_hierarchical_rng(&[1], &[Hierarchy::Member, Hierarchy::group(0, 2)], &mut rng)
+            .is_err());
+    }
+
+    #[test]
+    fn duplicated_shares_err() {
+        let groups = [Hierarchy::Member, Hierarchy::Member];
+        let shares = make_shares(2, &groups);
+        assert!(SecretSharing::<POLY>(2)
+            .recover_hierarchical(&groups, [&shares[0], &shares[0]])
+            .is_err());
+    }
+}
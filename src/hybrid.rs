@@ -0,0 +1,297 @@
+//! Computational secret sharing for large secrets
+//! ([Krawczyk, "Secret Sharing Made Short"](https://doi.org/10.1007/3-540-48329-2_12)).
+//!
+//! The secret is encrypted with ChaCha20-Poly1305 under a random key. Only the 32-byte key is
+//! Shamir-shared, and the ciphertext is dispersed with an information dispersal algorithm so that
+//! any `k` pieces recover it. Each share therefore holds about `|S| / k` bytes plus the key share,
+//! instead of `|S|` bytes.
+//!
+//! Every sharing uses a fresh key, so the nonce is fixed to zero.
+
+use alloc::vec::Vec;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use hashbrown::HashSet;
+
+#[cfg(feature = "zeroize_memory")]
+use zeroize::Zeroize;
+
+use super::field::GF256;
+use super::ida;
+use super::math;
+use super::share::ShareWithX;
+use super::SecretSharing;
+
+/// Length in bytes of the encryption key shared among the participants.
+pub const KEY_LENGTH: usize = 32;
+/// Length in bytes of the header of a serialized `HybridShare`.
+const HEADER_LENGTH: usize = 1 + KEY_LENGTH + 8;
+
+/// A share of a secret split with `dealer_hybrid_rng`. Can be serialized to and from a byte array
+/// as `[x, key..., length (u64 big endian), piece...]`.
+#[derive(Clone)]
+#[cfg_attr(feature = "zeroize_memory", derive(Zeroize))]
+#[cfg_attr(feature = "zeroize_memory", zeroize(drop))]
+pub struct HybridShare<const POLY: u16> {
+    /// The x coordinate of the share.
+    pub x: u8,
+    /// The Shamir share of the encryption key.
+    pub key: Vec<u8>,
+    /// The length in bytes of the whole ciphertext.
+    pub length: u64,
+    /// The piece of the ciphertext held by this share.
+    pub piece: Vec<u8>,
+}
+
+impl<const POLY: u16> From<&HybridShare<POLY>> for Vec<u8> {
+    fn from(s: &HybridShare<POLY>) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LENGTH + s.piece.len());
+        bytes.push(s.x);
+        bytes.extend_from_slice(&s.key);
+        bytes.extend_from_slice(&s.length.to_be_bytes());
+        bytes.extend_from_slice(&s.piece);
+        bytes
+    }
+}
+
+impl<const POLY: u16> core::convert::TryFrom<&[u8]> for HybridShare<POLY> {
+    type Error = &'static str;
+
+    fn try_from(s: &[u8]) -> Result<HybridShare<POLY>, Self::Error> {
+        if s.len() <= HEADER_LENGTH {
+            return Err("A HybridShare must be longer than its 41 bytes header");
+        }
+        if s[0] == 0 {
+            return Err("A HybridShare x coordinate must not be 0");
+        }
+        let mut length = [0u8; 8];
+        length.copy_from_slice(&s[1 + KEY_LENGTH..HEADER_LENGTH]);
+        Ok(HybridShare {
+            x: s[0],
+            key: s[1..1 + KEY_LENGTH].to_vec(),
+            length: u64::from_be_bytes(length),
+            piece: s[HEADER_LENGTH..].to_vec(),
+        })
+    }
+}
+
+impl<const POLY: u16> SecretSharing<POLY> {
+    /// Given a `secret` byte slice, returns an `Iterator` along new hybrid shares, each about
+    /// `|secret| / k` bytes long plus a 41 bytes header.
+    /// The threshold must be at least 1 and the maximum number of shares that can be generated is 255.
+    ///
+    /// Example:
+    /// ```
+    /// # use ssskit::{ HybridShare, SecretSharing };
+    /// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    /// # const POLY: u16 = 0x11d_u16;
+    /// # let sss = SecretSharing::<POLY>(3);
+    /// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// let secret = vec![7u8; 3000];
+    /// let shares = sss
+    ///     .dealer_hybrid_rng(&secret, &mut rng)
+    ///     .unwrap()
+    ///     .take(5)
+    ///     .collect::<Vec<HybridShare<POLY>>>();
+    /// // Each piece holds a third of the ciphertext
+    /// assert_eq!(shares[0].piece.len(), 1006);
+    /// assert_eq!(sss.recover_hybrid(&shares[2..]).unwrap(), secret);
+    /// ```
+    pub fn dealer_hybrid_rng<R: rand::Rng>(
+        &self,
+        secret: &[u8],
+        rng: &mut R,
+    ) -> Result<impl Iterator<Item = HybridShare<POLY>>, &'static str> {
+        if self.0 == 0 {
+            return Err("A threshold of at least 1 is required");
+        }
+
+        let mut key = [0u8; KEY_LENGTH];
+        rng.fill(&mut key);
+        let ciphertext = ChaCha20Poly1305::new(Key::from_slice(&key))
+            .encrypt(Nonce::from_slice(&[0; 12]), secret)
+            .map_err(|_| "Secret is too long to be encrypted")?;
+        let key_shares = self.dealer_rng(&key, rng);
+
+        #[cfg(feature = "zeroize_memory")]
+        key.zeroize();
+
+        let length = ciphertext.len() as u64;
+        Ok(key_shares
+            .zip(ida::disperse::<POLY>(&ciphertext, self.0))
+            .zip(1..=u8::MAX)
+            .map(move |((key, piece), x)| HybridShare {
+                x,
+                key: key.y.iter().map(|y| y.0).collect(),
+                length,
+                piece,
+            }))
+    }
+
+    /// Same as `dealer_hybrid_rng`, using `rand::thread_rng`.
+    #[cfg(feature = "std")]
+    pub fn dealer_hybrid(
+        &self,
+        secret: &[u8],
+    ) -> Result<impl Iterator<Item = HybridShare<POLY>>, &'static str> {
+        let mut rng = rand::thread_rng();
+        self.dealer_hybrid_rng(secret, &mut rng)
+    }
+
+    /// Given an iterable collection of hybrid shares, recovers the original secret.
+    /// If the shares are corrupted, were not issued together, or the threshold is wrong,
+    /// the decryption fails and an `Err` is returned.
+    ///
+    /// Example:
+    /// ```
+    /// # use ssskit::{ HybridShare, SecretSharing };
+    /// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    /// # const POLY: u16 = 0x11d_u16;
+    /// # let sss = SecretSharing::<POLY>(2);
+    /// # let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// let mut shares = sss
+    ///     .dealer_hybrid_rng(&[1, 2, 3, 4], &mut rng)
+    ///     .unwrap()
+    ///     .take(3)
+    ///     .collect::<Vec<HybridShare<POLY>>>();
+    /// // Corrupt a share
+    /// shares[0].piece[0] ^= 1;
+    /// assert!(sss.recover_hybrid(&shares[..2]).is_err());
+    /// assert_eq!(sss.recover_hybrid(&shares[1..]).unwrap(), vec![1, 2, 3, 4]);
+    /// ```
+    pub fn recover_hybrid<'a, T>(&self, shares: T) -> Result<Vec<u8>, &'static str>
+    where
+        T: IntoIterator<Item = &'a HybridShare<POLY>>,
+        T::IntoIter: Iterator<Item = &'a HybridShare<POLY>>,
+    {
+        let mut xs = HashSet::new();
+        let shares: Vec<&HybridShare<POLY>> = shares.into_iter().collect();
+        if shares.len() < self.0 as usize || self.0 == 0 {
+            return Err("Not enough shares to recover original secret");
+        }
+        if !shares.iter().all(|s| s.x != 0 && xs.insert(s.x)) {
+            return Err("Shares must have distinct non-zero x coordinates");
+        }
+
+        let shares = &shares[..self.0 as usize];
+        let length = shares[0].length;
+        let piece_length = length.div_ceil(self.0 as u64);
+        if !shares.iter().all(|s| {
+            s.length == length && s.piece.len() as u64 == piece_length && s.key.len() == KEY_LENGTH
+        }) {
+            return Err("All shares must have the same length");
+        }
+
+        let key_shares: Vec<ShareWithX<POLY>> = shares
+            .iter()
+            .map(|s| ShareWithX {
+                x: GF256(s.x),
+                y: s.key.iter().map(|y| GF256(*y)).collect(),
+            })
+            .collect();
+        let pieces: Vec<(u8, &[u8])> = shares.iter().map(|s| (s.x, s.piece.as_slice())).collect();
+
+        let key = math::interpolate(&key_shares);
+        let ciphertext = ida::reconstruct::<POLY>(&pieces, self.0, length as usize);
+        let secret = ChaCha20Poly1305::new(Key::from_slice(&key))
+            .decrypt(Nonce::from_slice(&[0; 12]), ciphertext.as_slice())
+            .map_err(|_| "Could not decrypt the secret, shares are corrupted or inconsistent");
+
+        #[cfg(feature = "zeroize_memory")]
+        {
+            let mut key = key;
+            key.zeroize();
+        }
+
+        secret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HybridShare;
+    use crate::SecretSharing;
+    use alloc::vec::Vec;
+    use core::convert::TryFrom;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    use rstest::rstest;
+
+    const POLY: u16 = 0x11d_u16;
+
+    fn make_shares(k: u8, secret: &[u8], n: usize) -> Vec<HybridShare<POLY>> {
+        let mut rng = ChaCha8Rng::from_seed([0x17; 32]);
+        SecretSharing::<POLY>(k)
+            .dealer_hybrid_rng(secret, &mut rng)
+            .unwrap()
+            .take(n)
+            .collect()
+    }
+
+    #[rstest]
+    #[case(1, 1, 0)]
+    #[case(1, 3, 100)]
+    #[case(3, 5, 1)]
+    #[case(5, 10, 10_000)]
+    #[case(10, 255, 1000)]
+    fn hybrid_roundtrip(#[case] k: u8, #[case] n: usize, #[case] length: usize) {
+        let sss = SecretSharing::<POLY>(k);
+        let secret: Vec<u8> = (0..length).map(|i| (i % 251) as u8).collect();
+        let shares = make_shares(k, &secret, n);
+        assert_eq!(shares.len(), n);
+        assert!(shares
+            .iter()
+            .all(|s| s.piece.len() == (length + 16).div_ceil(k as usize)));
+
+        assert_eq!(sss.recover_hybrid(&shares).unwrap(), secret);
+        assert_eq!(
+            sss.recover_hybrid(&shares[n - k as usize..]).unwrap(),
+            secret
+        );
+        assert_eq!(
+            sss.recover_hybrid(shares.iter().rev().take(k as usize))
+                .unwrap(),
+            secret
+        );
+    }
+
+    #[test]
+    fn serialization_roundtrip() {
+        let sss = SecretSharing::<POLY>(3);
+        let shares: Vec<HybridShare<POLY>> = make_shares(3, &[42; 100], 4)
+            .iter()
+            .map(|s| HybridShare::try_from(Vec::from(s).as_slice()).unwrap())
+            .collect();
+        assert_eq!(sss.recover_hybrid(&shares[1..]).unwrap(), [42; 100]);
+
+        assert!(HybridShare::<POLY>::try_from(&[1u8; 41][..]).is_err());
+        assert!(HybridShare::<POLY>::try_from(&[0u8; 50][..]).is_err());
+    }
+
+    #[test]
+    fn corrupted_or_mismatched_shares_err() {
+        let sss = SecretSharing::<POLY>(3);
+        let mut shares = make_shares(3, &[1; 64], 4);
+        shares[0].key[3] ^= 1;
+        assert!(sss.recover_hybrid(&shares[..3]).is_err());
+        assert!(sss.recover_hybrid(&shares[1..]).is_ok());
+
+        assert!(SecretSharing::<POLY>(2)
+            .recover_hybrid(&shares[1..])
+            .is_err());
+        assert!(SecretSharing::<POLY>(4)
+            .recover_hybrid(&shares[1..])
+            .is_err());
+
+        let mut shares = make_shares(3, &[1; 64], 3);
+        shares[2].x = shares[1].x;
+        assert!(sss.recover_hybrid(&shares).is_err());
+    }
+
+    #[test]
+    fn zero_threshold_err() {
+        let mut rng = ChaCha8Rng::from_seed([0x17; 32]);
+        assert!(SecretSharing::<POLY>(0)
+            .dealer_hybrid_rng(&[1, 2, 3], &mut rng)
+            .is_err());
+    }
+}
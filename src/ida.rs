@@ -0,0 +1,284 @@
+// A module which contains the information dispersal algorithm (IDA) used to split data into pieces
+// of `|data| / k` bytes, any `k` of which recover the data.
+// The dispersal is systematic: the data is split into `k` stripes, which are the pieces at `x = 1..=k`,
+// and every other piece is the evaluation of the polynomials going through the stripes' bytes.
+// Unlike secret sharing, the pieces leak the data: this is erasure coding for availability only.
+
+use alloc::vec::Vec;
+use hashbrown::HashSet;
+
+use crate::field::GF256;
+use crate::math;
+
+/// Length in bytes of the header of a serialized `Piece`.
+const PIECE_HEADER_LENGTH: usize = 1 + 8;
+
+/// A piece of dispersed data. Can be serialized to and from a byte array as
+/// `[index, length (u64 big endian), data...]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Piece {
+    /// The index of the piece, i.e. its x coordinate, in `1..=255`.
+    pub index: u8,
+    /// The length in bytes of the whole dispersed data.
+    pub length: u64,
+    /// The bytes held by this piece.
+    pub data: Vec<u8>,
+}
+
+impl From<&Piece> for Vec<u8> {
+    fn from(p: &Piece) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(PIECE_HEADER_LENGTH + p.data.len());
+        bytes.push(p.index);
+        bytes.extend_from_slice(&p.length.to_be_bytes());
+        bytes.extend_from_slice(&p.data);
+        bytes
+    }
+}
+
+impl core::convert::TryFrom<&[u8]> for Piece {
+    type Error = &'static str;
+
+    fn try_from(p: &[u8]) -> Result<Piece, Self::Error> {
+        if p.len() < PIECE_HEADER_LENGTH {
+            return Err("A Piece must be at least 9 bytes long");
+        }
+        if p[0] == 0 {
+            return Err("A Piece index must not be 0");
+        }
+        let mut length = [0u8; 8];
+        length.copy_from_slice(&p[1..PIECE_HEADER_LENGTH]);
+        Ok(Piece {
+            index: p[0],
+            length: u64::from_be_bytes(length),
+            data: p[PIECE_HEADER_LENGTH..].to_vec(),
+        })
+    }
+}
+
+/// Tuple struct which implements Rabin's information dispersal over a 256 bits Galois Field, i.e.
+/// `k`-of-`n` erasure coding where each piece is `|data| / k` bytes long.
+/// Its only parameter is the number of pieces required to recover the data.
+///
+/// The output is systematic: the first `k` pieces are the data itself, split in `k` stripes.
+/// The pieces do not hide the data, use `SecretSharing` for confidentiality.
+///
+/// Usage example:
+/// ```
+/// # use ssskit::{ InformationDispersal, Piece };
+/// # const POLY: u16 = 0x11d_u16;
+/// let ida = InformationDispersal::<POLY>(3);
+/// let data = b"availability critical data".to_vec();
+/// let pieces = ida.encode(&data).unwrap().take(5).collect::<Vec<Piece>>();
+/// // The first pieces are plain data
+/// assert_eq!(pieces[0].data, b"availabil");
+/// // Any 3 pieces recover the data
+/// assert_eq!(ida.decode(&pieces[2..]).unwrap(), data);
+/// ```
+pub struct InformationDispersal<const POLY: u16>(pub u8);
+
+impl<const POLY: u16> InformationDispersal<POLY> {
+    /// Given a `data` byte slice, returns an `Iterator` along its pieces, each
+    /// `data.len().div_ceil(k)` bytes long.
+    /// The number of required pieces must be at least 1 and the maximum number of pieces that can
+    /// be generated is 255.
+    pub fn encode(&self, data: &[u8]) -> Result<impl Iterator<Item = Piece>, &'static str> {
+        if self.0 == 0 {
+            return Err("At least 1 piece must be required to recover the data");
+        }
+
+        let length = data.len() as u64;
+        Ok(disperse::<POLY>(data, self.0)
+            .zip(1..=u8::MAX)
+            .map(move |(data, index)| Piece {
+                index,
+                length,
+                data,
+            }))
+    }
+
+    /// Given an iterable collection of pieces, recovers the original data.
+    /// Only the first `k` pieces are used, which must have distinct indices and the same length.
+    ///
+    /// Example:
+    /// ```
+    /// # use ssskit::{ InformationDispersal, Piece };
+    /// # const POLY: u16 = 0x11d_u16;
+    /// let ida = InformationDispersal::<POLY>(2);
+    /// let pieces = ida.encode(&[1, 2, 3, 4, 5]).unwrap().collect::<Vec<Piece>>();
+    /// // Decode from serialized pieces 200 and 17
+    /// let received = [Vec::from(&pieces[199]), Vec::from(&pieces[16])];
+    /// let pieces = received
+    ///     .iter()
+    ///     .map(|p| Piece::try_from(p.as_slice()).unwrap())
+    ///     .collect::<Vec<Piece>>();
+    /// assert_eq!(ida.decode(&pieces).unwrap(), vec![1, 2, 3, 4, 5]);
+    /// ```
+    pub fn decode<'a, T>(&self, pieces: T) -> Result<Vec<u8>, &'static str>
+    where
+        T: IntoIterator<Item = &'a Piece>,
+        T::IntoIter: Iterator<Item = &'a Piece>,
+    {
+        let mut indices = HashSet::new();
+        let pieces: Vec<&Piece> = pieces.into_iter().take(self.0 as usize).collect();
+        if pieces.len() < self.0 as usize || self.0 == 0 {
+            return Err("Not enough pieces to recover original data");
+        }
+        if !pieces
+            .iter()
+            .all(|p| p.index != 0 && indices.insert(p.index))
+        {
+            return Err("Pieces must have distinct non-zero indices");
+        }
+
+        let length = pieces[0].length;
+        let piece_length = length.div_ceil(self.0 as u64);
+        if !pieces
+            .iter()
+            .all(|p| p.length == length && p.data.len() as u64 == piece_length)
+        {
+            return Err("All pieces must have the same length");
+        }
+
+        let pieces: Vec<(u8, &[u8])> = pieces
+            .iter()
+            .map(|p| (p.index, p.data.as_slice()))
+            .collect();
+        Ok(reconstruct::<POLY>(&pieces, self.0, length as usize))
+    }
+}
+
+// Returns an iterator over the pieces of `data` for `x = 1..=255`, each `data.len().div_ceil(k)`
+// bytes long. The data is zero padded to a multiple of `k` bytes.
+pub(crate) fn disperse<const POLY: u16>(data: &[u8], k: u8) -> impl Iterator<Item = Vec<u8>> {
+    let k = k as usize;
+    let length = data.len().div_ceil(k);
+    let mut stripes = alloc::vec![0u8; length * k];
+    stripes[..data.len()].copy_from_slice(data);
+
+    let x_samples: Vec<GF256<POLY>> = (1..=k).map(|x| GF256(x as u8)).collect();
+    (1..=u8::MAX).map(move |x| {
+        if (x as usize) <= k {
+            let start = (x as usize - 1) * length;
+            return stripes[start..start + length].to_vec();
+        }
+
+        let weights = math::lagrange_coefficients(&x_samples, GF256(x));
+        (0..length)
+            .map(|c| {
+                weights
+                    .iter()
+                    .zip(stripes.chunks_exact(length))
+                    .map(|(w, stripe)| w.clone() * GF256(stripe[c]))
+                    .sum::<GF256<POLY>>()
+                    .0
+            })
+            .collect()
+    })
+}
+
+// Recovers the first `length` bytes of the dispersed data from `k` pieces given as `(x, piece)` pairs,
+// which must have distinct `x` coordinates and the same length.
+pub(crate) fn reconstruct<const POLY: u16>(
+    pieces: &[(u8, &[u8])],
+    k: u8,
+    length: usize,
+) -> Vec<u8> {
+    let pieces = &pieces[..k as usize];
+    let x_samples: Vec<GF256<POLY>> = pieces.iter().map(|(x, _)| GF256(*x)).collect();
+    let piece_length = pieces[0].1.len();
+
+    let mut data = Vec::with_capacity(piece_length * k as usize);
+    for x in 1..=k {
+        let weights = math::lagrange_coefficients(&x_samples, GF256(x));
+        data.extend((0..piece_length).map(|c| {
+            weights
+                .iter()
+                .zip(pieces.iter())
+                .map(|(w, (_, piece))| w.clone() * GF256(piece[c]))
+                .sum::<GF256<POLY>>()
+                .0
+        }));
+    }
+    data.truncate(length);
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{disperse, reconstruct, InformationDispersal, Piece};
+    use alloc::{vec, vec::Vec};
+    use core::convert::TryFrom;
+    use rstest::rstest;
+
+    const POLY: u16 = 0x11d_u16;
+
+    #[rstest]
+    #[case(1, 0)]
+    #[case(1, 10)]
+    #[case(3, 10)]
+    #[case(4, 1000)]
+    fn disperse_is_systematic(#[case] k: u8, #[case] length: usize) {
+        let data: Vec<u8> = (0..length).map(|i| (i * 3) as u8).collect();
+        let pieces: Vec<Vec<u8>> = disperse::<POLY>(&data, k).take(k as usize).collect();
+        let concatenated: Vec<u8> = pieces.concat();
+        assert_eq!(&concatenated[..length], data.as_slice());
+        assert!(pieces
+            .iter()
+            .all(|p| p.len() == length.div_ceil(k as usize)));
+    }
+
+    #[rstest]
+    #[case(1, 5, vec![4])]
+    #[case(3, 7, vec![6, 1, 4])]
+    #[case(4, 255, vec![254, 100, 3, 200])]
+    fn reconstruct_works(#[case] k: u8, #[case] n: usize, #[case] xs: Vec<usize>) {
+        let data: Vec<u8> = (0..101).map(|i| (i * 7) as u8).collect();
+        let pieces: Vec<Vec<u8>> = disperse::<POLY>(&data, k).take(n).collect();
+        let selected: Vec<(u8, &[u8])> = xs
+            .iter()
+            .map(|&x| (x as u8, pieces[x - 1].as_slice()))
+            .collect();
+        assert_eq!(reconstruct::<POLY>(&selected, k, data.len()), data);
+    }
+
+    #[rstest]
+    #[case(1, 3, 0)]
+    #[case(2, 2, 1)]
+    #[case(3, 10, 1000)]
+    #[case(16, 255, 4096)]
+    fn encode_decode_roundtrip(#[case] k: u8, #[case] n: usize, #[case] length: usize) {
+        let ida = InformationDispersal::<POLY>(k);
+        let data: Vec<u8> = (0..length).map(|i| (i * 13) as u8).collect();
+        let pieces: Vec<Piece> = ida.encode(&data).unwrap().take(n).collect();
+        assert!(pieces
+            .iter()
+            .all(|p| p.data.len() == length.div_ceil(k as usize) && p.length == length as u64));
+
+        assert_eq!(ida.decode(&pieces).unwrap(), data);
+        assert_eq!(ida.decode(pieces.iter().rev()).unwrap(), data);
+        let serialized: Vec<Piece> = pieces
+            .iter()
+            .map(|p| Piece::try_from(Vec::from(p).as_slice()).unwrap())
+            .collect();
+        assert_eq!(serialized, pieces);
+    }
+
+    #[test]
+    fn invalid_pieces_err() {
+        let ida = InformationDispersal::<POLY>(3);
+        let pieces: Vec<Piece> = ida.encode(&[1; 30]).unwrap().take(4).collect();
+        assert!(ida.decode(&pieces[..2]).is_err());
+
+        let duplicated = vec![pieces[0].clone(), pieces[1].clone(), pieces[0].clone()];
+        assert!(ida.decode(&duplicated).is_err());
+
+        let mut truncated = pieces.clone();
+        truncated[1].data.pop();
+        assert!(ida.decode(&truncated).is_err());
+        assert!(InformationDispersal::<POLY>(2).decode(&pieces).is_err());
+
+        assert!(InformationDispersal::<POLY>(0).encode(&[1]).is_err());
+        assert!(Piece::try_from(&[1u8; 8][..]).is_err());
+        assert!(Piece::try_from(&[0u8; 9][..]).is_err());
+    }
+}
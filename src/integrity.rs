@@ -0,0 +1,277 @@
+//! Secret integrity digest, embedded in the sharing polynomials as in
+//! [SLIP-0039](https://github.com/satoshilabs/slips/blob/master/slip-0039.md#digest-share).
+//!
+//! Along with the secret `S` at `x = 0`, every polynomial passes through the digest share
+//! `D = HMAC-SHA256(key = R, msg = S)[..4] || R` at `x = 255`, where `R` are `|S| - 4` random bytes.
+//! Recovery interpolates both points and rejects the secret if the digest does not match.
+//! Participant shares are therefore limited to `x` in `[1, 254]`.
+
+use alloc::vec::Vec;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use super::field::GF256;
+use super::math;
+use super::share::Share;
+use super::SecretSharing;
+
+/// Length in bytes of the digest embedded along with the secret.
+pub const DIGEST_LENGTH: usize = 4;
+/// Minimum length in bytes of a secret protected by a digest.
+pub const MIN_SECRET_LENGTH: usize = 16;
+/// The `x` coordinate at which the digest share is embedded.
+const DIGEST_INDEX: u8 = 255;
+
+/// Errors returned when recovering a secret protected by an integrity digest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntegrityError {
+    /// The shares could not be combined, see [`SecretSharing::recover`].
+    Recover(&'static str),
+    /// A secret was recovered but it does not match its embedded digest, i.e. a share is
+    /// corrupted, too few shares were given, or the wrong `POLY` was used.
+    IntegrityCheckFailed,
+}
+
+impl From<&'static str> for IntegrityError {
+    fn from(e: &'static str) -> Self {
+        IntegrityError::Recover(e)
+    }
+}
+
+fn digest(random: &[u8], secret: &[u8]) -> Hmac<Sha256> {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(random).expect("HMAC accepts any key");
+    mac.update(secret);
+    mac
+}
+
+impl<const POLY: u16> SecretSharing<POLY> {
+    /// Same as `dealer_rng`, but embeds an HMAC digest of the secret in the shares so that
+    /// `recover_integrity` can tell whether the recovered secret is correct.
+    ///
+    /// The threshold must be at least 2 and the secret at least [`MIN_SECRET_LENGTH`] bytes long.
+    /// The maximum number of shares that can be generated is 254.
+    ///
+    /// Example:
+    /// ```
+    /// # use ssskit::{ SecretSharing, Share };
+    /// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    /// # const POLY: u16 = 0x11d_u16;
+    /// # let sss = SecretSharing::<POLY>(3);
+    /// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// let dealer = sss.dealer_integrity_rng(&[7; 16], &mut rng).unwrap();
+    /// let shares = dealer.take(3).map(Some).collect::<Vec<Option<Share<POLY>>>>();
+    /// let secret = sss.recover_integrity(&shares).unwrap();
+    /// assert_eq!(secret, vec![7; 16]);
+    /// ```
+    pub fn dealer_integrity_rng<R: rand::Rng>(
+        &self,
+        secret: &[u8],
+        rng: &mut R,
+    ) -> Result<impl Iterator<Item = Share<POLY>>, &'static str> {
+        if self.0 < 2 {
+            return Err("A threshold of at least 2 is required to embed a digest");
+        }
+        if secret.len() < MIN_SECRET_LENGTH {
+            return Err("Secret must be at least 16 bytes long to embed a digest");
+        }
+
+        let mut random = alloc::vec![0u8; secret.len() - DIGEST_LENGTH];
+        rng.fill(random.as_mut_slice());
+
+        let mut digest_share = Vec::with_capacity(secret.len());
+        digest_share
+            .extend_from_slice(&digest(&random, secret).finalize().into_bytes()[..DIGEST_LENGTH]);
+        digest_share.extend_from_slice(&random);
+
+        // `random_polynomial` returns the coefficients from the highest degree down, so the
+        // leading one is overwritten to have the polynomial pass through the digest share.
+        let x = GF256::<POLY>(DIGEST_INDEX);
+        let leading = (1..self.0).fold(GF256(1), |acc, _| acc * x.clone());
+        let mut polys = Vec::with_capacity(secret.len());
+        for (s, d) in secret.iter().zip(digest_share.iter()) {
+            let mut poly = math::random_polynomial(GF256(*s), self.0, rng);
+            poly[0] = GF256(0);
+            let rest = poly
+                .iter()
+                .fold(GF256(0), |acc, c| acc * x.clone() + c.clone());
+            poly[0] = (GF256(*d) - rest) / leading.clone();
+            polys.push(poly);
+        }
+
+        #[cfg(feature = "zeroize_memory")]
+        {
+            use zeroize::Zeroize;
+            random.zeroize();
+            digest_share.zeroize();
+        }
+
+        Ok(math::get_evaluator(polys).take(DIGEST_INDEX as usize - 1))
+    }
+
+    /// Same as `dealer`, but embeds an HMAC digest of the secret in the shares.
+    /// See `dealer_integrity_rng`.
+    #[cfg(feature = "std")]
+    pub fn dealer_integrity(
+        &self,
+        secret: &[u8],
+    ) -> Result<impl Iterator<Item = Share<POLY>>, &'static str> {
+        let mut rng = rand::thread_rng();
+        self.dealer_integrity_rng(secret, &mut rng)
+    }
+
+    /// Given an iterable collection of shares generated by `dealer_integrity_rng`, recovers the
+    /// original secret and verifies it against the embedded digest.
+    /// Returns `IntegrityError::IntegrityCheckFailed` if the digest does not match.
+    ///
+    /// Example:
+    /// ```
+    /// # use ssskit::{ IntegrityError, SecretSharing, Share };
+    /// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    /// # const POLY: u16 = 0x11d_u16;
+    /// # let sss = SecretSharing::<POLY>(3);
+    /// # let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// # let dealer = sss.dealer_integrity_rng(&[7; 16], &mut rng).unwrap();
+    /// let mut shares = dealer.take(3).map(Some).collect::<Vec<Option<Share<POLY>>>>();
+    /// // Corrupt a share
+    /// shares[0].as_mut().unwrap().y[0].0 ^= 1;
+    /// assert_eq!(
+    ///     sss.recover_integrity(&shares),
+    ///     Err(IntegrityError::IntegrityCheckFailed)
+    /// );
+    /// ```
+    pub fn recover_integrity<'a, T>(&self, shares: T) -> Result<Vec<u8>, IntegrityError>
+    where
+        T: IntoIterator<Item = &'a Option<Share<POLY>>>,
+        T::IntoIter: Iterator<Item = &'a Option<Share<POLY>>>,
+    {
+        let values = self.collect_shares(shares)?;
+        if values[0].y.len() < MIN_SECRET_LENGTH {
+            return Err(IntegrityError::IntegrityCheckFailed);
+        }
+
+        let secret = math::interpolate(&values);
+        let x_samples: Vec<GF256<POLY>> = values.iter().map(|s| s.x.clone()).collect();
+        let digest_share: Vec<u8> = (0..secret.len())
+            .map(|i| {
+                let y_samples: Vec<GF256<POLY>> = values.iter().map(|s| s.y[i].clone()).collect();
+                math::interpolate_polynomial(&x_samples, &y_samples, GF256(DIGEST_INDEX)).0
+            })
+            .collect();
+
+        let valid = digest(&digest_share[DIGEST_LENGTH..], &secret)
+            .verify_truncated_left(&digest_share[..DIGEST_LENGTH])
+            .is_ok();
+
+        #[cfg(feature = "zeroize_memory")]
+        {
+            use zeroize::Zeroize;
+            let mut digest_share = digest_share;
+            digest_share.zeroize();
+        }
+
+        if valid {
+            Ok(secret)
+        } else {
+            Err(IntegrityError::IntegrityCheckFailed)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IntegrityError;
+    use crate::{SecretSharing, Share};
+    use alloc::vec::Vec;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    use rstest::rstest;
+
+    const POLY: u16 = 0x11b_u16;
+
+    fn make_shares(sss: &SecretSharing<POLY>, secret: &[u8], n: usize) -> Vec<Share<POLY>> {
+        let mut rng = ChaCha8Rng::from_seed([0x42; 32]);
+        sss.dealer_integrity_rng(secret, &mut rng)
+            .unwrap()
+            .take(n)
+            .collect()
+    }
+
+    #[rstest]
+    #[case(2, 2)]
+    #[case(3, 5)]
+    #[case(10, 254)]
+    fn integrity_roundtrip(#[case] k: u8, #[case] n: usize) {
+        let sss = SecretSharing::<POLY>(k);
+        let secret: Vec<u8> = (0..32).collect();
+        let shares: Vec<Option<Share<POLY>>> = make_shares(&sss, &secret, n)
+            .into_iter()
+            .map(Some)
+            .collect();
+        assert_eq!(shares.len(), n);
+        assert_eq!(sss.recover_integrity(&shares).unwrap(), secret);
+        // The digest does not change the plain recovery
+        assert_eq!(sss.recover(&shares).unwrap(), secret);
+    }
+
+    #[test]
+    fn corrupted_share_fails() {
+        let sss = SecretSharing::<POLY>(3);
+        let mut shares: Vec<Option<Share<POLY>>> = make_shares(&sss, &[9; 16], 3)
+            .into_iter()
+            .map(Some)
+            .collect();
+        shares[1].as_mut().unwrap().y[5].0 ^= 0x01;
+        assert_eq!(
+            sss.recover_integrity(&shares),
+            Err(IntegrityError::IntegrityCheckFailed)
+        );
+    }
+
+    #[test]
+    fn wrong_threshold_fails() {
+        let sss = SecretSharing::<POLY>(4);
+        let shares: Vec<Option<Share<POLY>>> = make_shares(&sss, &[9; 16], 3)
+            .into_iter()
+            .map(Some)
+            .collect();
+        assert_eq!(
+            SecretSharing::<POLY>(3).recover_integrity(&shares),
+            Err(IntegrityError::IntegrityCheckFailed)
+        );
+        assert!(matches!(
+            sss.recover_integrity(&shares),
+            Err(IntegrityError::Recover(_))
+        ));
+    }
+
+    #[test]
+    fn wrong_poly_fails() {
+        let sss = SecretSharing::<POLY>(3);
+        let shares = make_shares(&sss, &[9; 16], 3);
+        let shares: Vec<Option<Share<0x11d>>> = shares
+            .iter()
+            .map(|s| Some(Share::<0x11d>::try_from(Vec::from(s).as_slice()).unwrap()))
+            .collect();
+        assert_eq!(
+            SecretSharing::<0x11d>(3).recover_integrity(&shares),
+            Err(IntegrityError::IntegrityCheckFailed)
+        );
+    }
+
+    #[test]
+    fn invalid_parameters_err() {
+        let mut rng = ChaCha8Rng::from_seed([0x42; 32]);
+        assert!(SecretSharing::<POLY>(1)
+            .dealer_integrity_rng(&[0; 16], &mut rng)
+            .is_err());
+        assert!(SecretSharing::<POLY>(2)
+            .dealer_integrity_rng(&[0; 15], &mut rng)
+            .is_err());
+        assert_eq!(
+            SecretSharing::<POLY>(2)
+                .dealer_integrity_rng(&[0; 16], &mut rng)
+                .unwrap()
+                .count(),
+            254
+        );
+    }
+}
@@ -61,10 +61,24 @@
 //! other behavior:
 //!
 //! - `std` — enables `dealer` convenience (uses `rand::thread_rng`). Without `std`, use `dealer_rng`.
+//!   Also enables `split_stream` and `combine_stream`, which share and recover secrets over
+//!   `std::io` streams in bounded memory.
 //! - `zeroize_memory` — enables `Zeroize` on share types to clear memory on drop.
 //! - default (no `share_x`) — `Share` stores only `y` values. The `x` coordinate is implicit
 //!   and derived from the iteration order (1-based) when generating or consuming shares.
 //! - `share_x` — `Share` stores both `x` and `y`. The `x` is carried with each share.
+//! - `merkle` — enables `commit_shares` and `recover_committed`, which commit to issued shares
+//!   with a SHA-256 Merkle root and per-share inclusion proofs.
+//! - `cli` — builds the `ssskit` command-line tool to split, combine, reshare and inspect shares.
+//! - `integrity` — enables `dealer_integrity_rng` and `recover_integrity`, which embed an HMAC
+//!   digest of the secret in the shares (SLIP-0039 style) and verify it at recovery.
+//! - `hybrid` — enables `dealer_hybrid_rng` and `recover_hybrid`, which encrypt large secrets with
+//!   ChaCha20-Poly1305, share only the key and disperse the ciphertext, so that each share is about
+//!   `|secret| / k` bytes long.
+//! - `frost` — enables `Frost`, FROST threshold signing (RFC 9591) of Ed25519 signatures with a
+//!   signing key shared over the scalar field of curve25519.
+//! - `elgamal` — enables `ElGamal`, threshold ElGamal decryption over ristretto255, where `k`
+//!   holders of a shared private key decrypt with DLEQ-proven `PartialDecryption`s.
 //!
 //! By default, `share_x` is disabled (no-x). To use `share_x`, enable `share_x` explicitly.
 //!
@@ -83,23 +97,122 @@
 //!   This supports both variants uniformly (with or without `x`).
 //! - `recover_shares`: fill a target of size `n` using `Option` positions (`None` for
 //!   unknowns). Positions map to indices `1..=n`.
+//! - `recover_robust`: same input as `recover`, but corrects up to `(n - k) / 2` corrupted shares
+//!   (Reed–Solomon decoding) and reports their positions.
+//! - `check_consistency`: checks that a pool of shares lies on a single polynomial and identifies
+//!   the inconsistent ones, before trusting a recovery.
+//! - [`InformationDispersal`]: `k`-of-`n` erasure coding of non-secret data (Rabin's IDA), where each
+//!   [`Piece`] is `|data| / k` bytes long and carries its index and the data length.
+//! - [`RampSharing`]: `(t, k, n)` ramp scheme where any `t` shares reveal nothing, any `k` shares
+//!   recover the secret, and each share is about `|secret| / (k - t)` bytes long.
+//! - [`PackedSharing`]: packed Shamir (Franklin–Yung) sharing of many equally long secrets, `k - t`
+//!   of them per polynomial, with `recover` for all the secrets and `unpack` for a single one.
+//! - `dealer_weighted_rng` / `recover_weighted`: weighted threshold sharing, where each participant
+//!   holds a [`ShareBundle`] of as many shares as their weight and the threshold is a total weight.
+//! - `dealer_hierarchical_rng` / `recover_hierarchical`: multi-level sharing among groups of
+//!   members, each with its own threshold, described by a [`Hierarchy`] of any depth. Each member
+//!   holds a [`MemberShare`] tagged with its path in the hierarchy.
+//! - [`Policy`]: general monotone access structures, as formulas of AND, OR and threshold gates
+//!   over named participants, shared recursively (Benaloh–Leichter). Each participant holds a
+//!   [`ParticipantBundle`] of component shares.
+//! - `refresh_rng`: proactive refresh of the shares without reconstructing the secret. Each holder
+//!   sends [`RefreshMessage`]s of a random sharing of zero (`refresh_messages_rng`) and adds the
+//!   ones it receives from every holder to its share (`apply_refresh`). Refreshed shares cannot be
+//!   combined with old ones.
+//! - `redistribute_rng`: moves a secret from a `(k, n)` to a new `(k', n')` committee without
+//!   reconstructing it. `k` old holders send [`RedistributionMessage`]s sharing their own share
+//!   (`redistribution_messages_rng`), which new holders combine (`combine_redistribution`).
+//! - `repair_share_rng`: computes a lost share, or enrolls a new holder, from `k` helpers exchanging
+//!   masked [`RepairMessage`]s. Unlike `recover_shares`, nobody sees another participant's share.
+//! - `revoke_rng`: revokes compromised shares, the remaining holders refreshing among themselves into
+//!   the next epoch. [`EpochShare`]s of different epochs are rejected by `recover_epoch`.
+//! - `dkg_rng`: generates a random secret shared among `n` parties without any dealer. Each party
+//!   deals [`DkgMessage`]s of a random contribution. Dealers answer [`DkgComplaint`]s with a
+//!   [`DkgResponse`], and dealers shown to be inconsistent are left out of the sum.
+//! - [`AdditiveSharing`]: `n`-of-`n` additive (XOR) sharing, with `additive_shares` converting `k`
+//!   Shamir shares into [`AdditiveShare`]s and `convert_additive_rng` converting them back.
+//! - `Share::try_add`, `try_sub`, `scale` and `add_constant`: linear operations on shares, so that
+//!   holders can compute shares of linear combinations of secrets and only reveal the result.
+//! - `multiply_beaver` / `multiply_grr_rng`: multiplication of shared secrets, with preprocessed
+//!   [`BeaverTriple`]s or with GRR degree reduction among `2k - 1` holders.
+//! - [`Combiner`]: incremental recovery from shares added one at a time (`SecretSharing::combiner`),
+//!   validating each share as it comes and reporting how many more are needed before `finalize`.
 //!
 //! In `share_x`, `x` in each `Share` is used directly. Without x-coordinate, the iterator index
 //! is used as `x` (1-based) during interpolation and resharing.
 #![cfg_attr(not(feature = "std"), no_std)]
 
+mod additive;
+mod combiner;
+mod dkg;
+#[cfg(feature = "elgamal")]
+mod elgamal;
 mod field;
+#[cfg(feature = "frost")]
+mod frost;
+mod hierarchy;
+#[cfg(feature = "hybrid")]
+mod hybrid;
+mod ida;
+#[cfg(feature = "integrity")]
+mod integrity;
+mod linear;
 mod math;
+#[cfg(feature = "merkle")]
+mod merkle;
+mod multiply;
+mod packed;
+mod policy;
+mod ramp;
+mod redistribute;
+mod refresh;
+mod repair;
+mod revoke;
+mod robust;
+#[cfg(any(feature = "frost", feature = "elgamal"))]
+mod scalar;
 mod share;
+#[cfg(feature = "std")]
+mod stream;
+mod weighted;
 
 extern crate alloc;
 
 use alloc::vec::Vec;
 use hashbrown::HashSet;
 
+pub use additive::{AdditiveShare, AdditiveSharing};
+pub use combiner::Combiner;
+pub use dkg::{DkgBlindedShare, DkgComplaint, DkgMessage, DkgResponse, DKG_CHALLENGE_LENGTH};
+#[cfg(feature = "elgamal")]
+pub use elgamal::{Ciphertext, ElGamal, ElGamalKeyShare, ElGamalPublicKey, PartialDecryption};
 use field::GF256;
 pub use field::PRIMITIVE_POLYS;
+#[cfg(feature = "frost")]
+pub use frost::{
+    Frost, KeyPackage, PublicKeyPackage, SignatureShare, SigningCommitments, SigningNonces,
+    SIGNATURE_LENGTH,
+};
+pub use hierarchy::{Hierarchy, MemberShare};
+#[cfg(feature = "hybrid")]
+pub use hybrid::{HybridShare, KEY_LENGTH};
+pub use ida::{InformationDispersal, Piece};
+#[cfg(feature = "integrity")]
+pub use integrity::{IntegrityError, DIGEST_LENGTH, MIN_SECRET_LENGTH};
+#[cfg(feature = "merkle")]
+pub use merkle::{commit_shares, MerkleProof, MerkleRoot};
+pub use multiply::BeaverTriple;
+pub use packed::PackedSharing;
+pub use policy::{ParticipantBundle, Policy};
+pub use ramp::RampSharing;
+pub use redistribute::RedistributionMessage;
+pub use refresh::RefreshMessage;
+pub use repair::RepairMessage;
+pub use revoke::EpochShare;
 pub use share::Share;
+#[cfg(feature = "std")]
+pub use stream::{StreamHeader, STREAM_CHUNK_LENGTH};
+pub use weighted::ShareBundle;
 
 use crate::share::ShareWithX;
 
@@ -204,7 +317,19 @@ impl<const POLY: u16> SecretSharing<POLY> {
     /// secret = sss.recover(&shares);
     /// // Not enough shares to recover secret
     /// assert!(secret.is_err());
-    pub fn recover<'a, T>(&self, shares: T) -> Result<Vec<u8>, &str>
+    pub fn recover<'a, T>(&self, shares: T) -> Result<Vec<u8>, &'static str>
+    where
+        T: IntoIterator<Item = &'a Option<Share<POLY>>>,
+        T::IntoIter: Iterator<Item = &'a Option<Share<POLY>>>,
+    {
+        let values = self.collect_shares(shares)?;
+        Ok(math::interpolate(&values))
+    }
+
+    /// Attaches the `x` coordinate to every known share (see the crate level API notes) and checks
+    /// that all shares have the same length and that there are enough distinct shares to reach the
+    /// threshold.
+    fn collect_shares<'a, T>(&self, shares: T) -> Result<Vec<ShareWithX<POLY>>, &'static str>
     where
         T: IntoIterator<Item = &'a Option<Share<POLY>>>,
         T::IntoIter: Iterator<Item = &'a Option<Share<POLY>>>,
@@ -246,7 +371,7 @@ impl<const POLY: u16> SecretSharing<POLY> {
         if keys.is_empty() || (keys.len() < self.0 as usize) {
             Err("Not enough shares to recover original secret")
         } else {
-            Ok(math::interpolate(&values))
+            Ok(values)
         }
     }
 
@@ -275,7 +400,11 @@ impl<const POLY: u16> SecretSharing<POLY> {
     /// let recovered_shares = sss.recover_shares([Some(&shares[0]), None, None], 3);
     /// // Not enough shares to recover shares
     /// assert!(recovered_shares.is_err());
-    pub fn recover_shares<'a, T>(&self, shares: T, n: usize) -> Result<Vec<Share<POLY>>, &str>
+    pub fn recover_shares<'a, T>(
+        &self,
+        shares: T,
+        n: usize,
+    ) -> Result<Vec<Share<POLY>>, &'static str>
     where
         T: IntoIterator<Item = Option<&'a Share<POLY>>>,
         T::IntoIter: Iterator<Item = Option<&'a Share<POLY>>>,
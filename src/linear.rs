@@ -0,0 +1,178 @@
+// Linear operations on shares. Shamir's secret sharing is linear: adding the shares at the same `x`
+// of two secrets yields a share of their sum, and scaling a share scales the secret. Holders can
+// then compute shares of linear combinations of secrets locally, and only reveal the result.
+
+use alloc::vec::Vec;
+
+use super::field::GF256;
+use super::share::Share;
+
+impl<const POLY: u16> Share<POLY> {
+    /// Checks that `other` is at the same `x` coordinate, with `share_x`, and has the same length.
+    pub(crate) fn check_compatible(&self, other: &Self) -> Result<(), &'static str> {
+        #[cfg(feature = "share_x")]
+        if self.x != other.x {
+            return Err("Shares must have the same x coordinate");
+        }
+        if self.y.len() != other.y.len() {
+            return Err("Shares must have the same length");
+        }
+        Ok(())
+    }
+
+    /// Returns a copy of the share with its y coordinates replaced by `y`.
+    pub(crate) fn with_y(&self, y: Vec<GF256<POLY>>) -> Self {
+        let mut share = self.clone();
+        share.y = y;
+        share
+    }
+
+    /// Adds the share `other` of another secret, shared with the same threshold, and returns a
+    /// share of the sum of both secrets. Without `share_x`, both shares must be at the same position.
+    ///
+    /// Example:
+    /// ```
+    /// # use ssskit::{ SecretSharing, Share };
+    /// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    /// # const POLY: u16 = 0x11d_u16;
+    /// # let sss = SecretSharing::<POLY>(2);
+    /// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// let a = sss.dealer_rng(&[1, 2], &mut rng).take(3).collect::<Vec<Share<POLY>>>();
+    /// let b = sss.dealer_rng(&[3, 3], &mut rng).take(3).collect::<Vec<Share<POLY>>>();
+    /// // Each holder computes a share of a + 2 * b locally
+    /// let sum = a
+    ///     .iter()
+    ///     .zip(b.iter())
+    ///     .map(|(a, b)| a.try_add(&b.scale(2)).ok())
+    ///     .collect::<Vec<Option<Share<POLY>>>>();
+    /// assert_eq!(sss.recover(&sum).unwrap(), vec![1 ^ 6, 2 ^ 6]);
+    /// ```
+    pub fn try_add(&self, other: &Self) -> Result<Self, &'static str> {
+        self.check_compatible(other)?;
+        Ok(self.with_y(
+            self.y
+                .iter()
+                .zip(other.y.iter())
+                .map(|(a, b)| a.clone() + b.clone())
+                .collect(),
+        ))
+    }
+
+    /// Subtracts the share `other` of another secret, shared with the same threshold, and returns
+    /// a share of the difference of both secrets. In GF(256) it is the same as `try_add`.
+    pub fn try_sub(&self, other: &Self) -> Result<Self, &'static str> {
+        self.check_compatible(other)?;
+        Ok(self.with_y(
+            self.y
+                .iter()
+                .zip(other.y.iter())
+                .map(|(a, b)| a.clone() - b.clone())
+                .collect(),
+        ))
+    }
+
+    /// Multiplies the share by the public constant `c`, and returns a share of the secret
+    /// multiplied by `c` in GF(256).
+    pub fn scale(&self, c: u8) -> Self {
+        self.with_y(self.y.iter().map(|y| y.clone() * GF256(c)).collect())
+    }
+
+    /// Adds the public constant `c`, as long as the secret, and returns a share of the secret plus
+    /// `c`. Every holder must add the same constant.
+    pub fn add_constant(&self, c: &[u8]) -> Result<Self, &'static str> {
+        if self.y.len() != c.len() {
+            return Err("The constant must have the same length as the share");
+        }
+        Ok(self.with_y(
+            self.y
+                .iter()
+                .zip(c.iter())
+                .map(|(y, c)| y.clone() + GF256(*c))
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GF256;
+    use crate::{SecretSharing, Share};
+    use alloc::{vec, vec::Vec};
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    use rstest::rstest;
+
+    const POLY: u16 = 0x11d_u16;
+
+    fn deal(sss: &SecretSharing<POLY>, secret: &[u8], seed: u8) -> Vec<Share<POLY>> {
+        let mut rng = ChaCha8Rng::from_seed([seed; 32]);
+        sss.dealer_rng(secret, &mut rng).take(5).collect()
+    }
+
+    fn field(a: u8) -> GF256<POLY> {
+        GF256(a)
+    }
+
+    #[rstest]
+    #[case(1, 0x53, 0xca)]
+    #[case(3, 0x02, 0x00)]
+    #[case(5, 0xff, 0x01)]
+    fn linear_combination(#[case] k: u8, #[case] c: u8, #[case] d: u8) {
+        let sss = SecretSharing::<POLY>(k);
+        let a = [10, 20, 30];
+        let b = [7, 0, 255];
+        let offset = [1, 2, 3];
+        let shares_a = deal(&sss, &a, 0x46);
+        let shares_b = deal(&sss, &b, 0x47);
+
+        // c * a - d * b + offset
+        let result: Vec<Option<Share<POLY>>> = shares_a
+            .iter()
+            .zip(shares_b.iter())
+            .map(|(a, b)| {
+                a.scale(c)
+                    .try_sub(&b.scale(d))
+                    .and_then(|s| s.add_constant(&offset))
+                    .ok()
+            })
+            .collect();
+        let expected: Vec<u8> = (0..3)
+            .map(|i| (field(c) * field(a[i]) - field(d) * field(b[i]) + field(offset[i])).0)
+            .collect();
+        assert_eq!(sss.recover(&result).unwrap(), expected);
+
+        let sum: Vec<Option<Share<POLY>>> = shares_a
+            .iter()
+            .zip(shares_b.iter())
+            .map(|(a, b)| a.try_add(b).ok())
+            .collect();
+        let expected: Vec<u8> = a.iter().zip(b.iter()).map(|(a, b)| a ^ b).collect();
+        assert_eq!(sss.recover(&sum).unwrap(), expected);
+    }
+
+    #[test]
+    fn mismatched_shares_err() {
+        let sss = SecretSharing::<POLY>(2);
+        let shares_a = deal(&sss, &[1, 2], 0x46);
+        let shares_b = deal(&sss, &[1, 2, 3], 0x47);
+        assert!(shares_a[0].try_add(&shares_b[0]).is_err());
+        assert!(shares_a[0].try_sub(&shares_b[0]).is_err());
+        assert!(shares_a[0].add_constant(&[1, 2, 3]).is_err());
+
+        #[cfg(feature = "share_x")]
+        {
+            let shares_b = deal(&sss, &[3, 4], 0x47);
+            assert!(shares_a[0].try_add(&shares_b[1]).is_err());
+            assert!(shares_a[0].try_sub(&shares_b[1]).is_err());
+            assert!(shares_a[0].try_add(&shares_b[0]).is_ok());
+        }
+    }
+
+    #[test]
+    fn scaling_by_zero_and_one() {
+        let sss = SecretSharing::<POLY>(3);
+        let shares = deal(&sss, &[9, 9], 0x46);
+        let ones: Vec<Option<Share<POLY>>> = shares.iter().map(|s| Some(s.scale(1))).collect();
+        assert_eq!(sss.recover(&ones).unwrap(), vec![9, 9]);
+        assert!(shares.iter().all(|s| s.scale(0).y.iter().all(|y| y.0 == 0)));
+    }
+}
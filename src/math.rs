@@ -12,18 +12,15 @@ use crate::share::ShareWithX;
 // Where each (key, value) pair corresponds to one share, where the key is the `x` and the value is a vector of `y`,
 // where each element corresponds to one of the secret's byte chunks.
 pub fn interpolate<const POLY: u16>(shares: &[ShareWithX<POLY>]) -> Vec<u8> {
+    let x_samples: Vec<GF256<POLY>> = shares.iter().map(|s| s.x.clone()).collect();
+    let weights = lagrange_coefficients(&x_samples, GF256(0));
+
     (0..shares[0].y.len())
         .map(|s| {
             shares
                 .iter()
-                .map(|s_i| {
-                    shares
-                        .iter()
-                        .filter(|s_j| s_j.x != s_i.x)
-                        .map(|s_j| s_j.x.clone() / (s_j.x.clone() - s_i.x.clone()))
-                        .product::<GF256<POLY>>()
-                        * s_i.y[s].clone()
-                })
+                .zip(weights.iter())
+                .map(|(s_i, w_i)| w_i.clone() * s_i.y[s].clone())
                 .sum::<GF256<POLY>>()
                 .0
         })
@@ -64,6 +61,208 @@ pub fn interpolate_polynomial<const POLY: u16>(
     result
 }
 
+/// Returns the coefficients of the polynomial of degree lower than N that goes through the N sample points.
+/// Coefficient degrees go from lower to higher in the returned vector order.
+pub fn interpolate_coefficients<const POLY: u16>(
+    x_samples: &[GF256<POLY>],
+    y_samples: &[GF256<POLY>],
+) -> Vec<GF256<POLY>> {
+    assert!(
+        x_samples.len() == y_samples.len(),
+        "sample length mistmatch"
+    );
+
+    // N(x) = (x - x_0)(x - x_1)...(x - x_{N-1})
+    let mut roots = vec![GF256(1)];
+    for x_j in x_samples {
+        roots.insert(0, GF256(0));
+        for i in 0..roots.len() - 1 {
+            roots[i] = roots[i].clone() - x_j.clone() * roots[i + 1].clone();
+        }
+    }
+
+    let mut result = vec![GF256(0); x_samples.len()];
+    for (x_i, y_i) in x_samples.iter().zip(y_samples) {
+        // N(x) / (x - x_i), by synthetic division
+        let mut basis = vec![GF256(0); x_samples.len()];
+        let mut carry = GF256(0);
+        for i in (0..basis.len()).rev() {
+            carry = roots[i + 1].clone() + carry * x_i.clone();
+            basis[i] = carry.clone();
+        }
+
+        let scale = y_i.clone() / evaluate(&basis, x_i.clone());
+        for (r, b) in result.iter_mut().zip(basis) {
+            *r = r.clone() + scale.clone() * b;
+        }
+    }
+
+    result
+}
+
+/// Evaluates the polynomial at `x`, with coefficient degrees going from lower to higher.
+pub fn evaluate<const POLY: u16>(poly: &[GF256<POLY>], x: GF256<POLY>) -> GF256<POLY> {
+    poly.iter()
+        .rev()
+        .fold(GF256(0), |acc, c| acc * x.clone() + c.clone())
+}
+
+/// Divides `num` by `den`, both with coefficient degrees going from lower to higher.
+/// Returns the quotient and the remainder. The leading coefficient of `den` must be non-zero.
+pub fn divide<const POLY: u16>(
+    num: &[GF256<POLY>],
+    den: &[GF256<POLY>],
+) -> (Vec<GF256<POLY>>, Vec<GF256<POLY>>) {
+    let degree = den.len() - 1;
+    let mut remainder = num.to_vec();
+    if remainder.len() <= degree {
+        return (Vec::new(), remainder);
+    }
+
+    let mut quotient = vec![GF256(0); remainder.len() - degree];
+    for i in (0..quotient.len()).rev() {
+        let coefficient = remainder[i + degree].clone() / den[degree].clone();
+        for (j, d) in den.iter().enumerate() {
+            remainder[i + j] = remainder[i + j].clone() - coefficient.clone() * d.clone();
+        }
+        quotient[i] = coefficient;
+    }
+    remainder.truncate(degree);
+
+    (quotient, remainder)
+}
+
+/// Solves a linear system given as an augmented matrix (each row being the coefficients followed by
+/// the constant term) using Gauss-Jordan elimination.
+/// Returns `None` if the system is inconsistent. Free variables of an underdetermined system are set to zero.
+pub fn solve<const POLY: u16>(mut rows: Vec<Vec<GF256<POLY>>>) -> Option<Vec<GF256<POLY>>> {
+    let unknowns = rows.first().map_or(0, |r| r.len() - 1);
+    let mut pivots = Vec::new();
+
+    for column in 0..unknowns {
+        let rank = pivots.len();
+        let Some(pivot) = (rank..rows.len()).find(|&i| rows[i][column].0 != 0) else {
+            continue;
+        };
+        rows.swap(rank, pivot);
+
+        let inverse = GF256(1) / rows[rank][column].clone();
+        for v in rows[rank].iter_mut() {
+            *v = v.clone() * inverse.clone();
+        }
+
+        let pivot_row = rows[rank].clone();
+        for (i, row) in rows.iter_mut().enumerate() {
+            if i == rank || row[column].0 == 0 {
+                continue;
+            }
+            let factor = row[column].clone();
+            for (v, p) in row.iter_mut().zip(pivot_row.iter()) {
+                *v = v.clone() - factor.clone() * p.clone();
+            }
+        }
+
+        pivots.push(column);
+        if pivots.len() == rows.len() {
+            break;
+        }
+    }
+
+    if rows[pivots.len()..].iter().any(|r| r[unknowns].0 != 0) {
+        return None;
+    }
+
+    let mut solution = vec![GF256(0); unknowns];
+    for (row, column) in rows.iter().zip(pivots) {
+        solution[column] = row[unknowns].clone();
+    }
+    Some(solution)
+}
+
+/// Decodes the sample points as a Reed-Solomon codeword of a polynomial of degree lower than `k`,
+/// using the [Berlekamp-Welch algorithm](https://en.wikipedia.org/wiki/Berlekamp%E2%80%93Welch_algorithm).
+/// Up to `(N - k) / 2` erroneous samples can be corrected.
+/// Returns the polynomial coefficients (from lower to higher degree) along with the indices of the
+/// erroneous samples, or `None` if there are too many errors to decode.
+pub fn berlekamp_welch<const POLY: u16>(
+    x_samples: &[GF256<POLY>],
+    y_samples: &[GF256<POLY>],
+    k: usize,
+) -> Option<(Vec<GF256<POLY>>, Vec<usize>)> {
+    let n = x_samples.len();
+    let errors_of = |poly: &[GF256<POLY>]| -> Vec<usize> {
+        (0..n)
+            .filter(|&i| evaluate(poly, x_samples[i].clone()) != y_samples[i])
+            .collect()
+    };
+
+    // Fast path: the polynomial through the first `k` samples goes through all others.
+    let poly = interpolate_coefficients(&x_samples[..k], &y_samples[..k]);
+    let errors = errors_of(&poly);
+    if errors.is_empty() {
+        return Some((poly, errors));
+    }
+
+    let e = (n - k) / 2;
+    if e == 0 {
+        return None;
+    }
+
+    // Unknowns are the coefficients of Q (degree < k + e) followed by those of the error locator E,
+    // which is monic of degree e, such that Q(x_i) = y_i * E(x_i) for every sample.
+    let rows = x_samples
+        .iter()
+        .zip(y_samples)
+        .map(|(x, y)| {
+            let mut row = Vec::with_capacity(k + 2 * e + 1);
+            let mut power = GF256(1);
+            for _ in 0..k + e {
+                row.push(power.clone());
+                power = power * x.clone();
+            }
+            let mut power = GF256(1);
+            for _ in 0..e {
+                row.push(GF256(0) - y.clone() * power.clone());
+                power = power * x.clone();
+            }
+            row.push(y.clone() * power);
+            row
+        })
+        .collect();
+
+    let solution = solve(rows)?;
+    let mut locator = solution[k + e..].to_vec();
+    locator.push(GF256(1));
+    let (poly, remainder) = divide(&solution[..k + e], &locator);
+    if remainder.iter().any(|r| r.0 != 0) {
+        return None;
+    }
+
+    let errors = errors_of(&poly);
+    if errors.len() > e {
+        return None;
+    }
+    Some((poly, errors))
+}
+
+/// Returns the Lagrange basis coefficients of the sample points at `x`, such that the value at `x`
+/// of the polynomial going through the samples is the sum of each `y` sample times its coefficient.
+pub fn lagrange_coefficients<const POLY: u16>(
+    x_samples: &[GF256<POLY>],
+    x: GF256<POLY>,
+) -> Vec<GF256<POLY>> {
+    x_samples
+        .iter()
+        .map(|x_i| {
+            x_samples
+                .iter()
+                .filter(|x_j| *x_j != x_i)
+                .map(|x_j| (x.clone() - x_j.clone()) / (x_i.clone() - x_j.clone()))
+                .product()
+        })
+        .collect()
+}
+
 /// Resharing a share at a given index.
 pub fn reshare<const POLY: u16>(
     shares: &[(GF256<POLY>, Share<POLY>)],
@@ -154,7 +353,11 @@ pub fn get_evaluator<const POLY: u16>(
 
 #[cfg(test)]
 mod tests {
-    use super::{get_evaluator, interpolate, random_polynomial, reshare, Share, ShareWithX, GF256};
+    use super::{
+        berlekamp_welch, divide, evaluate, get_evaluator, interpolate, interpolate_coefficients,
+        interpolate_polynomial, lagrange_coefficients, random_polynomial, reshare, solve, Share,
+        ShareWithX, GF256,
+    };
     use alloc::{vec, vec::Vec};
     use rand_chacha::rand_core::SeedableRng;
     use rstest::rstest;
@@ -215,4 +418,103 @@ mod tests {
         let share = reshare(&shares, index);
         assert_eq!(share.y, shares[index - 1].1.y);
     }
+
+    #[rstest]
+    #[case([0x90; 32], 1)]
+    #[case([0x10; 32], 5)]
+    #[case([0x20; 32], 16)]
+    fn interpolate_coefficients_works(#[case] seed: [u8; 32], #[case] k: usize) {
+        let mut rng = rand_chacha::ChaCha8Rng::from_seed(seed);
+        let mut poly = random_polynomial::<_, POLY>(GF256(185), k as u8, &mut rng);
+        let iter = get_evaluator(vec![poly.clone()]);
+        let (x_samples, y_samples): (Vec<GF256<POLY>>, Vec<GF256<POLY>>) = iter
+            .take(k)
+            .enumerate()
+            .map(|(i, s)| (GF256(i as u8 + 1), s.y[0].clone()))
+            .unzip();
+        poly.reverse();
+        assert_eq!(interpolate_coefficients(&x_samples, &y_samples), poly);
+    }
+
+    #[test]
+    fn evaluate_works() {
+        let poly = vec![GF256::<POLY>(5), GF256(2), GF256(3)];
+        assert_eq!(evaluate(&poly, GF256(1)), GF256(4));
+        assert_eq!(evaluate(&poly, GF256(2)), GF256(13));
+    }
+
+    #[test]
+    fn divide_works() {
+        // (x^2 + 3x + 2) = (x + 1)(x + 2) over GF(2^8)
+        let num = vec![GF256::<POLY>(2), GF256(3), GF256(1)];
+        let (quotient, remainder) = divide(&num, &[GF256(1), GF256(1)]);
+        assert_eq!(quotient, vec![GF256(2), GF256(1)]);
+        assert_eq!(remainder, vec![GF256(0)]);
+
+        let (quotient, remainder) = divide(&[GF256::<POLY>(7)], &[GF256(1), GF256(1)]);
+        assert!(quotient.is_empty());
+        assert_eq!(remainder, vec![GF256(7)]);
+    }
+
+    #[test]
+    fn solve_works() {
+        // x + y = 3, x + 2y = 5
+        let rows = vec![
+            vec![GF256::<POLY>(1), GF256(1), GF256(3)],
+            vec![GF256(1), GF256(2), GF256(5)],
+        ];
+        let solution = solve(rows).unwrap();
+        assert_eq!(solution[0].clone() + solution[1].clone(), GF256(3));
+        assert_eq!(
+            solution[0].clone() + GF256(2) * solution[1].clone(),
+            GF256(5)
+        );
+
+        // x + y = 3, x + y = 4
+        let rows = vec![
+            vec![GF256::<POLY>(1), GF256(1), GF256(3)],
+            vec![GF256(1), GF256(1), GF256(4)],
+        ];
+        assert!(solve(rows).is_none());
+    }
+
+    #[rstest]
+    #[case(3, 7, vec![])]
+    #[case(3, 7, vec![2, 6])]
+    #[case(4, 10, vec![0, 1, 9])]
+    fn berlekamp_welch_works(#[case] k: usize, #[case] n: usize, #[case] bad: Vec<usize>) {
+        let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+        let mut poly = random_polynomial::<_, POLY>(GF256(185), k as u8, &mut rng);
+        let iter = get_evaluator(vec![poly.clone()]);
+        let (x_samples, mut y_samples): (Vec<GF256<POLY>>, Vec<GF256<POLY>>) = iter
+            .take(n)
+            .enumerate()
+            .map(|(i, s)| (GF256(i as u8 + 1), s.y[0].clone()))
+            .unzip();
+        for &i in &bad {
+            y_samples[i] = y_samples[i].clone() + GF256(0x11);
+        }
+
+        let (decoded, errors) = berlekamp_welch(&x_samples, &y_samples, k).unwrap();
+        poly.reverse();
+        assert_eq!(decoded, poly);
+        assert_eq!(errors, bad);
+    }
+
+    #[test]
+    fn lagrange_coefficients_works() {
+        let x_samples = vec![GF256::<POLY>(1), GF256(2), GF256(3)];
+        let y_samples = vec![GF256::<POLY>(4), GF256(13), GF256(3)];
+        for x in [0, 4, 255] {
+            let value = lagrange_coefficients(&x_samples, GF256(x))
+                .into_iter()
+                .zip(y_samples.iter())
+                .map(|(c, y)| c * y.clone())
+                .sum::<GF256<POLY>>();
+            assert_eq!(
+                value,
+                interpolate_polynomial(&x_samples, &y_samples, GF256(x))
+            );
+        }
+    }
 }
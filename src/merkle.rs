@@ -0,0 +1,355 @@
+//! Merkle commitments to issued shares.
+//!
+//! The dealer hashes the serialized bytes of every share into a binary Merkle tree and
+//! publishes the root. Each share holder receives an inclusion proof for its own share, so
+//! that any share can later be checked against the single published root.
+//!
+//! Leaves and inner nodes are domain separated (`0x00 || share` and `0x01 || left || right`)
+//! and an odd node at the end of a level is promoted unchanged to the next level. The root binds
+//! the number of committed shares (`0x02 || leaf_count || top`), so that a proof cannot claim a
+//! smaller tree whose top is an inner node of the real one.
+
+use alloc::vec::Vec;
+use sha2::{Digest, Sha256};
+
+use super::share::Share;
+use super::SecretSharing;
+
+/// Length in bytes of a Merkle node hash (SHA-256).
+pub const HASH_LENGTH: usize = 32;
+
+/// Root of the Merkle tree built over a set of shares.
+pub type MerkleRoot = [u8; HASH_LENGTH];
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+const ROOT_PREFIX: u8 = 0x02;
+
+fn hash_leaf(bytes: &[u8]) -> MerkleRoot {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(bytes);
+    hasher.finalize().into()
+}
+
+fn hash_node(left: &MerkleRoot, right: &MerkleRoot) -> MerkleRoot {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+fn hash_root(leaf_count: u8, top: &MerkleRoot) -> MerkleRoot {
+    let mut hasher = Sha256::new();
+    hasher.update([ROOT_PREFIX, leaf_count]);
+    hasher.update(top);
+    hasher.finalize().into()
+}
+
+/// Inclusion proof of a single share in a Merkle tree of `leaf_count` shares.
+///
+/// Can be serialized to and from a byte array as `[index, leaf_count, path...]`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MerkleProof {
+    /// Position of the share in the committed list (0-based).
+    pub index: u8,
+    /// Number of shares committed to in the tree.
+    pub leaf_count: u8,
+    /// Sibling hashes from the leaf up to the root.
+    pub path: Vec<MerkleRoot>,
+}
+
+impl MerkleProof {
+    /// Checks that `share` is the leaf at `self.index` of the tree with the given `root`.
+    pub fn verify<const POLY: u16>(&self, share: &Share<POLY>, root: &MerkleRoot) -> bool {
+        if self.index >= self.leaf_count {
+            return false;
+        }
+
+        let mut node = hash_leaf(&Vec::from(share));
+        let mut index = self.index as usize;
+        let mut width = self.leaf_count as usize;
+        let mut path = self.path.iter();
+
+        while width > 1 {
+            if index % 2 == 1 {
+                match path.next() {
+                    Some(sibling) => node = hash_node(sibling, &node),
+                    None => return false,
+                }
+            } else if index + 1 < width {
+                match path.next() {
+                    Some(sibling) => node = hash_node(&node, sibling),
+                    None => return false,
+                }
+            }
+            index /= 2;
+            width = width.div_ceil(2);
+        }
+
+        path.next().is_none() && &hash_root(self.leaf_count, &node) == root
+    }
+}
+
+/// Converts a MerkleProof to a vector of bytes.
+impl From<&MerkleProof> for Vec<u8> {
+    fn from(p: &MerkleProof) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(2 + p.path.len() * HASH_LENGTH);
+        bytes.push(p.index);
+        bytes.push(p.leaf_count);
+        for sibling in &p.path {
+            bytes.extend_from_slice(sibling);
+        }
+        bytes
+    }
+}
+
+impl core::convert::TryFrom<&[u8]> for MerkleProof {
+    type Error = &'static str;
+
+    fn try_from(s: &[u8]) -> Result<MerkleProof, Self::Error> {
+        if s.len() < 2 || !(s.len() - 2).is_multiple_of(HASH_LENGTH) {
+            Err("A MerkleProof must be 2 bytes followed by whole hashes")
+        } else {
+            let path = s[2..]
+                .chunks_exact(HASH_LENGTH)
+                .map(|c| c.try_into().unwrap())
+                .collect();
+            Ok(MerkleProof {
+                index: s[0],
+                leaf_count: s[1],
+                path,
+            })
+        }
+    }
+}
+
+/// Hashes every share and returns the Merkle root together with one inclusion proof per share,
+/// in the same order as `shares`.
+///
+/// Example:
+/// ```
+/// # use ssskit::{commit_shares, SecretSharing, Share};
+/// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+/// # const POLY: u16 = 0x11d_u16;
+/// let sss = SecretSharing::<POLY>(3);
+/// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+/// let shares: Vec<Share<POLY>> = sss.dealer_rng(&[1, 2, 3], &mut rng).take(5).collect();
+/// // Publish `root`, hand out `proofs[i]` along with `shares[i]`
+/// let (root, proofs) = commit_shares(&shares).unwrap();
+/// assert!(proofs[2].verify(&shares[2], &root));
+/// ```
+pub fn commit_shares<const POLY: u16>(
+    shares: &[Share<POLY>],
+) -> Result<(MerkleRoot, Vec<MerkleProof>), &'static str> {
+    if shares.is_empty() || shares.len() > u8::MAX as usize {
+        return Err("Between 1 and 255 shares can be committed to");
+    }
+
+    let mut levels: Vec<Vec<MerkleRoot>> =
+        alloc::vec![shares.iter().map(|s| hash_leaf(&Vec::from(s))).collect()];
+
+    while levels[levels.len() - 1].len() > 1 {
+        let level = &levels[levels.len() - 1];
+        let next = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => hash_node(left, right),
+                [single] => *single,
+                _ => unreachable!(),
+            })
+            .collect();
+        levels.push(next);
+    }
+
+    let root = hash_root(shares.len() as u8, &levels[levels.len() - 1][0]);
+    let proofs = (0..shares.len())
+        .map(|leaf| {
+            let mut index = leaf;
+            let mut path = Vec::new();
+            for level in &levels[..levels.len() - 1] {
+                let sibling = index ^ 1;
+                if sibling < level.len() {
+                    path.push(level[sibling]);
+                }
+                index /= 2;
+            }
+            MerkleProof {
+                index: leaf as u8,
+                leaf_count: shares.len() as u8,
+                path,
+            }
+        })
+        .collect();
+
+    Ok((root, proofs))
+}
+
+impl<const POLY: u16> SecretSharing<POLY> {
+    /// Given an iterable collection of shares paired with their inclusion proofs, drops the shares
+    /// whose proof does not verify against `root` and recovers the original secret from the rest.
+    /// Returns the secret along with the positions of the rejected shares.
+    ///
+    /// Without `share_x`, a proof must also have been issued for the share's position, as that is
+    /// where its `x` coordinate comes from.
+    ///
+    /// Example:
+    /// ```
+    /// # use ssskit::{commit_shares, MerkleProof, SecretSharing, Share};
+    /// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    /// # const POLY: u16 = 0x11d_u16;
+    /// # let sss = SecretSharing::<POLY>(2);
+    /// # let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// # let shares: Vec<Share<POLY>> = sss.dealer_rng(&[1, 2], &mut rng).take(3).collect();
+    /// let (root, proofs) = commit_shares(&shares).unwrap();
+    /// let committed: Vec<Option<(Share<POLY>, MerkleProof)>> = shares
+    ///     .into_iter()
+    ///     .zip(proofs)
+    ///     .map(Some)
+    ///     .collect();
+    /// let (secret, rejected) = sss.recover_committed(&committed, &root).unwrap();
+    /// assert_eq!(secret, vec![1, 2]);
+    /// assert!(rejected.is_empty());
+    /// ```
+    pub fn recover_committed<'a, T>(
+        &self,
+        shares: T,
+        root: &MerkleRoot,
+    ) -> Result<(Vec<u8>, Vec<usize>), &'static str>
+    where
+        T: IntoIterator<Item = &'a Option<(Share<POLY>, MerkleProof)>>,
+        T::IntoIter: Iterator<Item = &'a Option<(Share<POLY>, MerkleProof)>>,
+    {
+        let mut rejected = Vec::new();
+        let mut accepted: Vec<Option<Share<POLY>>> = Vec::new();
+
+        #[allow(unused_variables)]
+        for (i, share) in shares.into_iter().enumerate() {
+            match share {
+                None => accepted.push(None),
+                Some((share, proof)) => {
+                    #[cfg(not(feature = "share_x"))]
+                    let valid = proof.index as usize == i && proof.verify(share, root);
+                    #[cfg(feature = "share_x")]
+                    let valid = proof.verify(share, root);
+
+                    if valid {
+                        accepted.push(Some(share.clone()));
+                    } else {
+                        rejected.push(i);
+                        accepted.push(None);
+                    }
+                }
+            }
+        }
+
+        let secret = self.recover(&accepted)?;
+        Ok((secret, rejected))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{commit_shares, hash_leaf, hash_node, MerkleProof};
+    use crate::{SecretSharing, Share};
+    use alloc::vec::Vec;
+    use core::convert::TryFrom;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    use rstest::rstest;
+
+    const POLY: u16 = 0x11d_u16;
+
+    fn make_shares(n: usize) -> Vec<Share<POLY>> {
+        let sss = SecretSharing::<POLY>(3);
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        sss.dealer_rng(&[1, 2, 3, 4], &mut rng).take(n).collect()
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(2)]
+    #[case(5)]
+    #[case(8)]
+    #[case(255)]
+    fn proofs_verify(#[case] n: usize) {
+        let shares = make_shares(n);
+        let (root, proofs) = commit_shares(&shares).unwrap();
+        assert_eq!(proofs.len(), n);
+        for (share, proof) in shares.iter().zip(proofs.iter()) {
+            assert!(proof.verify(share, &root));
+        }
+    }
+
+    #[test]
+    fn tampered_share_fails() {
+        let shares = make_shares(5);
+        let (root, proofs) = commit_shares(&shares).unwrap();
+        let mut tampered = shares[1].clone();
+        tampered.y[0].0 ^= 1;
+        assert!(!proofs[1].verify(&tampered, &root));
+        assert!(!proofs[0].verify(&shares[1], &root));
+    }
+
+    #[test]
+    fn forged_leaf_count_fails() {
+        let sss = SecretSharing::<POLY>(2);
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        let shares: Vec<Share<POLY>> = sss.dealer_rng(&[1, 2], &mut rng).take(3).collect();
+        let (root, proofs) = commit_shares(&shares).unwrap();
+
+        // Claims a tree of 2 leaves whose top is the inner node over the first 2 shares
+        let forged = MerkleProof {
+            index: 1,
+            leaf_count: 2,
+            path: alloc::vec![hash_node(
+                &hash_leaf(&Vec::from(&shares[0])),
+                &hash_leaf(&Vec::from(&shares[1]))
+            )],
+        };
+        assert!(!forged.verify(&shares[2], &root));
+
+        let committed = [
+            Some((shares[0].clone(), proofs[0].clone())),
+            Some((shares[2].clone(), forged)),
+            None,
+        ];
+        assert!(sss.recover_committed(&committed, &root).is_err());
+    }
+
+    #[test]
+    fn proof_serialization_roundtrip() {
+        let shares = make_shares(7);
+        let (root, proofs) = commit_shares(&shares).unwrap();
+        let bytes = Vec::from(&proofs[6]);
+        let proof = MerkleProof::try_from(bytes.as_slice()).unwrap();
+        assert_eq!(proof, proofs[6]);
+        assert!(proof.verify(&shares[6], &root));
+        assert!(MerkleProof::try_from(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn recover_committed_flags_bad_shares() {
+        let sss = SecretSharing::<POLY>(3);
+        let shares = make_shares(5);
+        let (root, proofs) = commit_shares(&shares).unwrap();
+
+        let mut committed: Vec<Option<(Share<POLY>, MerkleProof)>> = shares
+            .iter()
+            .cloned()
+            .zip(proofs.iter().cloned())
+            .map(Some)
+            .collect();
+        if let Some((share, _)) = committed[3].as_mut() {
+            share.y[2].0 ^= 0xff;
+        }
+
+        let (secret, rejected) = sss.recover_committed(&committed, &root).unwrap();
+        assert_eq!(secret, alloc::vec![1, 2, 3, 4]);
+        assert_eq!(rejected, alloc::vec![3]);
+
+        committed[0] = None;
+        committed[1] = None;
+        assert!(sss.recover_committed(&committed, &root).is_err());
+    }
+}
@@ -0,0 +1,427 @@
+// Multiplication of secret-shared values, byte by byte in GF(256), by two methods.
+// Beaver triples: a dealer preprocesses sharings of random `a`, `b` and `c = a * b`. To multiply `x`
+// by `y`, holders open `d = x - a` and `e = y - b`, which reveal nothing, and then compute shares of
+// `x * y = c + d * b + e * a + d * e` locally.
+// GRR (Gennaro-Rabin-Rabin) degree reduction: the local product of two shares is a share of `x * y`
+// on a polynomial of degree `2k - 2`, which `2k - 1` holders redistribute with threshold `k`.
+
+use alloc::vec::Vec;
+
+use super::field::GF256;
+use super::redistribute::RedistributionMessage;
+use super::share::Share;
+use super::SecretSharing;
+
+/// The shares of a Beaver triple held by one holder: shares of random `a` and `b`, and of
+/// `c = a * b` byte by byte. Each triple must only be used for a single multiplication.
+#[derive(Clone)]
+pub struct BeaverTriple<const POLY: u16> {
+    /// The share of `a`.
+    pub a: Share<POLY>,
+    /// The share of `b`.
+    pub b: Share<POLY>,
+    /// The share of `c = a * b`.
+    pub c: Share<POLY>,
+}
+
+/// Multiplies the y coordinates of two shares at the same `x` byte by byte, which yields a share of
+/// the product on a polynomial of twice the degree.
+fn local_product<const POLY: u16>(
+    x: &Share<POLY>,
+    y: &Share<POLY>,
+) -> Result<Share<POLY>, &'static str> {
+    x.check_compatible(y)?;
+    Ok(x.with_y(
+        x.y.iter()
+            .zip(y.y.iter())
+            .map(|(x, y)| x.clone() * y.clone())
+            .collect(),
+    ))
+}
+
+/// Returns the share of `c + d * b + e * a + d * e`, where `d` and `e` are public.
+fn beaver_combine<const POLY: u16>(
+    triple: &BeaverTriple<POLY>,
+    d: &[u8],
+    e: &[u8],
+) -> Result<Share<POLY>, &'static str> {
+    triple.c.check_compatible(&triple.a)?;
+    triple.c.check_compatible(&triple.b)?;
+    if d.len() != triple.c.y.len() || e.len() != triple.c.y.len() {
+        return Err("The opened values must have the same length as the triple");
+    }
+    Ok(triple.c.with_y(
+        (0..d.len())
+            .map(|i| {
+                let (d, e) = (GF256(d[i]), GF256(e[i]));
+                triple.c.y[i].clone()
+                    + d.clone() * triple.b.y[i].clone()
+                    + e.clone() * triple.a.y[i].clone()
+                    + d * e
+            })
+            .collect(),
+    ))
+}
+
+impl<const POLY: u16> SecretSharing<POLY> {
+    /// Preprocessing of Beaver triples by a trusted dealer: returns the triples of `length` bytes of
+    /// the `n` holders at `x = 1..=n`. A random number generator has to be provided.
+    pub fn beaver_triples_rng<R: rand::Rng>(
+        &self,
+        length: usize,
+        n: u8,
+        rng: &mut R,
+    ) -> Result<Vec<BeaverTriple<POLY>>, &'static str> {
+        if self.0 == 0 || n < self.0 {
+            return Err("A threshold of at least 1 and at most n is required");
+        }
+
+        let mut a = alloc::vec![0u8; length];
+        rng.fill(a.as_mut_slice());
+        let mut b = alloc::vec![0u8; length];
+        rng.fill(b.as_mut_slice());
+        let c: Vec<u8> = a
+            .iter()
+            .zip(b.iter())
+            .map(|(a, b)| (GF256::<POLY>(*a) * GF256(*b)).0)
+            .collect();
+
+        let triples = self
+            .dealer_rng(&a, rng)
+            .zip(self.dealer_rng(&b, rng))
+            .zip(self.dealer_rng(&c, rng))
+            .take(n as usize)
+            .map(|((a, b), c)| BeaverTriple { a, b, c })
+            .collect();
+
+        #[cfg(feature = "zeroize_memory")]
+        {
+            use zeroize::Zeroize;
+            let mut c = c;
+            a.zeroize();
+            b.zeroize();
+            c.zeroize();
+        }
+
+        Ok(triples)
+    }
+
+    /// Same as `beaver_triples_rng`, using `rand::thread_rng`.
+    #[cfg(feature = "std")]
+    pub fn beaver_triples(
+        &self,
+        length: usize,
+        n: u8,
+    ) -> Result<Vec<BeaverTriple<POLY>>, &'static str> {
+        let mut rng = rand::thread_rng();
+        self.beaver_triples_rng(length, n, &mut rng)
+    }
+
+    /// First round of a Beaver multiplication, run by each holder: returns its shares of
+    /// `d = x - a` and `e = y - b`, to be opened with `recover`.
+    pub fn beaver_open(
+        &self,
+        x: &Share<POLY>,
+        y: &Share<POLY>,
+        triple: &BeaverTriple<POLY>,
+    ) -> Result<(Share<POLY>, Share<POLY>), &'static str> {
+        Ok((x.try_sub(&triple.a)?, y.try_sub(&triple.b)?))
+    }
+
+    /// Second round of a Beaver multiplication, run by each holder: returns its share of `x * y`
+    /// from its triple and the opened `d` and `e`.
+    pub fn beaver_multiply(
+        &self,
+        triple: &BeaverTriple<POLY>,
+        d: &[u8],
+        e: &[u8],
+    ) -> Result<Share<POLY>, &'static str> {
+        beaver_combine(triple, d, e)
+    }
+
+    /// Multiplies the secrets shared by `x` and `y` in-process with Beaver triples, as each holder
+    /// would in the protocol. Shares are passed as in `recover`, and the triple of the holder at
+    /// position `i` is `triples[i]`. Returns the shares of the product at the same positions.
+    ///
+    /// Example:
+    /// ```
+    /// # use ssskit::{ SecretSharing, Share };
+    /// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    /// # const POLY: u16 = 0x11d_u16;
+    /// # let sss = SecretSharing::<POLY>(2);
+    /// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// let triples = sss.beaver_triples_rng(1, 3, &mut rng).unwrap();
+    /// let x = sss.dealer_rng(&[3], &mut rng).take(3).map(Some).collect::<Vec<_>>();
+    /// let y = sss.dealer_rng(&[7], &mut rng).take(3).map(Some).collect::<Vec<_>>();
+    /// let z = sss.multiply_beaver(&x, &y, &triples).unwrap();
+    /// // 3 * 7 in GF(256)
+    /// assert_eq!(sss.recover(&z).unwrap(), vec![9]);
+    /// ```
+    pub fn multiply_beaver(
+        &self,
+        x: &[Option<Share<POLY>>],
+        y: &[Option<Share<POLY>>],
+        triples: &[BeaverTriple<POLY>],
+    ) -> Result<Vec<Option<Share<POLY>>>, &'static str> {
+        if x.len() != y.len() || x.len() > triples.len() {
+            return Err("Every holder must have a share of both secrets and a triple");
+        }
+
+        let mut d = Vec::with_capacity(x.len());
+        let mut e = Vec::with_capacity(x.len());
+        for ((x, y), triple) in x.iter().zip(y.iter()).zip(triples.iter()) {
+            let (d_i, e_i) = match (x, y) {
+                (Some(x), Some(y)) => {
+                    let (d_i, e_i) = self.beaver_open(x, y, triple)?;
+                    (Some(d_i), Some(e_i))
+                }
+                (None, None) => (None, None),
+                _ => return Err("Every holder must have a share of both secrets and a triple"),
+            };
+            d.push(d_i);
+            e.push(e_i);
+        }
+        let d = self.recover(&d)?;
+        let e = self.recover(&e)?;
+
+        x.iter()
+            .zip(triples.iter())
+            .map(|(x, triple)| {
+                x.as_ref()
+                    .map(|_| self.beaver_multiply(triple, &d, &e))
+                    .transpose()
+            })
+            .collect()
+    }
+
+    /// First round of a GRR multiplication, run by the holder at `from`, one of `2k - 1` holders:
+    /// reshares the local product of its shares of `x` and `y` with this threshold among the holders
+    /// at `to`. A random number generator has to be provided.
+    pub fn grr_messages_rng<R: rand::Rng>(
+        &self,
+        from: u8,
+        x: &Share<POLY>,
+        y: &Share<POLY>,
+        to: &[u8],
+        rng: &mut R,
+    ) -> Result<Vec<RedistributionMessage<POLY>>, &'static str> {
+        let product = local_product(x, y)?;
+        self.redistribution_messages_rng(from, &product, to, rng)
+    }
+
+    /// Same as `grr_messages_rng`, using `rand::thread_rng`.
+    #[cfg(feature = "std")]
+    pub fn grr_messages(
+        &self,
+        from: u8,
+        x: &Share<POLY>,
+        y: &Share<POLY>,
+        to: &[u8],
+    ) -> Result<Vec<RedistributionMessage<POLY>>, &'static str> {
+        let mut rng = rand::thread_rng();
+        self.grr_messages_rng(from, x, y, to, &mut rng)
+    }
+
+    /// Second round of a GRR multiplication, run by the holder at `to`: combines the messages of
+    /// the first `2k - 1` holders into its share of `x * y` with this threshold.
+    /// Without `share_x`, the share must be placed at position `to - 1`.
+    pub fn grr_combine<'a, T>(&self, to: u8, messages: T) -> Result<Share<POLY>, &'static str>
+    where
+        T: IntoIterator<Item = &'a RedistributionMessage<POLY>>,
+        T::IntoIter: Iterator<Item = &'a RedistributionMessage<POLY>>,
+    {
+        self.product_sharing()?.combine_redistribution(to, messages)
+    }
+
+    /// The sharing of local products, with threshold `2k - 1`.
+    fn product_sharing(&self) -> Result<SecretSharing<POLY>, &'static str> {
+        match self.0 {
+            0 => Err("A threshold of at least 1 is required"),
+            k if k > 128 => Err("A threshold of at most 128 is required to multiply shares"),
+            k => Ok(SecretSharing(2 * k - 1)),
+        }
+    }
+
+    /// Multiplies the secrets shared by `x` and `y` in-process with GRR degree reduction, as each
+    /// holder would in the protocol. Shares are passed as in `recover`, and at least `2k - 1`
+    /// holders are required. Returns the shares of the product at the same positions.
+    ///
+    /// Example:
+    /// ```
+    /// # use ssskit::{ SecretSharing, Share };
+    /// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    /// # const POLY: u16 = 0x11d_u16;
+    /// # let sss = SecretSharing::<POLY>(2);
+    /// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// let x = sss.dealer_rng(&[3], &mut rng).take(3).map(Some).collect::<Vec<_>>();
+    /// let y = sss.dealer_rng(&[7], &mut rng).take(3).map(Some).collect::<Vec<_>>();
+    /// let z = sss.multiply_grr_rng(&x, &y, &mut rng).unwrap();
+    /// assert_eq!(sss.recover(&z).unwrap(), vec![9]);
+    /// ```
+    pub fn multiply_grr_rng<R: rand::Rng>(
+        &self,
+        x: &[Option<Share<POLY>>],
+        y: &[Option<Share<POLY>>],
+        rng: &mut R,
+    ) -> Result<Vec<Option<Share<POLY>>>, &'static str> {
+        if x.len() != y.len()
+            || x.iter()
+                .zip(y.iter())
+                .any(|(x, y)| x.is_some() != y.is_some())
+        {
+            return Err("Every holder must have a share of both secrets");
+        }
+        let product = self.product_sharing()?;
+        let (positions, values) = product.collect_positioned(x)?;
+        let holders: Vec<u8> = values.iter().map(|s| s.x.0).collect();
+
+        let mut messages = Vec::with_capacity(product.0 as usize * holders.len());
+        for (&i, &from) in positions
+            .iter()
+            .zip(holders.iter())
+            .take(product.0 as usize)
+        {
+            let (x, y) = (x[i].as_ref().unwrap(), y[i].as_ref().unwrap());
+            messages.extend(self.grr_messages_rng(from, x, y, &holders, rng)?);
+        }
+
+        let mut shares = alloc::vec![None; x.len()];
+        for (&i, &to) in positions.iter().zip(holders.iter()) {
+            shares[i] = Some(self.grr_combine(to, messages.iter().filter(|m| m.to == to))?);
+        }
+        Ok(shares)
+    }
+
+    /// Same as `multiply_grr_rng`, using `rand::thread_rng`.
+    #[cfg(feature = "std")]
+    pub fn multiply_grr(
+        &self,
+        x: &[Option<Share<POLY>>],
+        y: &[Option<Share<POLY>>],
+    ) -> Result<Vec<Option<Share<POLY>>>, &'static str> {
+        let mut rng = rand::thread_rng();
+        self.multiply_grr_rng(x, y, &mut rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GF256;
+    use crate::{SecretSharing, Share};
+    use alloc::vec::Vec;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    use rstest::rstest;
+
+    const POLY: u16 = 0x11d_u16;
+
+    const X: [u8; 4] = [0, 1, 0x53, 0xff];
+    const Y: [u8; 4] = [0x12, 0xca, 0xca, 0x02];
+
+    fn deal(
+        sss: &SecretSharing<POLY>,
+        secret: &[u8],
+        n: usize,
+        rng: &mut ChaCha8Rng,
+    ) -> Vec<Option<Share<POLY>>> {
+        sss.dealer_rng(secret, rng).take(n).map(Some).collect()
+    }
+
+    fn product() -> Vec<u8> {
+        X.iter()
+            .zip(Y.iter())
+            .map(|(x, y)| (GF256::<POLY>(*x) * GF256(*y)).0)
+            .collect()
+    }
+
+    #[rstest]
+    #[case(1, 1)]
+    #[case(2, 3)]
+    #[case(3, 7)]
+    #[case(10, 20)]
+    fn beaver_multiplication(#[case] k: u8, #[case] n: usize) {
+        let sss = SecretSharing::<POLY>(k);
+        let mut rng = ChaCha8Rng::from_seed([0x47; 32]);
+        let triples = sss.beaver_triples_rng(X.len(), n as u8, &mut rng).unwrap();
+        let mut x = deal(&sss, &X, n, &mut rng);
+        let mut y = deal(&sss, &Y, n, &mut rng);
+        // Only k holders take part
+        for i in 0..n - k as usize {
+            x[i] = None;
+            y[i] = None;
+        }
+
+        let z = sss.multiply_beaver(&x, &y, &triples).unwrap();
+        assert_eq!(sss.recover(&z).unwrap(), product());
+        assert!(z.iter().take(n - k as usize).all(|s| s.is_none()));
+
+        // The triple itself is consistent
+        let c: Vec<Option<Share<POLY>>> = triples.iter().map(|t| Some(t.c.clone())).collect();
+        let a: Vec<Option<Share<POLY>>> = triples.iter().map(|t| Some(t.a.clone())).collect();
+        let b: Vec<Option<Share<POLY>>> = triples.iter().map(|t| Some(t.b.clone())).collect();
+        let expected: Vec<u8> = sss
+            .recover(&a)
+            .unwrap()
+            .iter()
+            .zip(sss.recover(&b).unwrap())
+            .map(|(a, b)| (GF256::<POLY>(*a) * GF256(b)).0)
+            .collect();
+        assert_eq!(sss.recover(&c).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case(1, 1)]
+    #[case(2, 3)]
+    #[case(3, 7)]
+    #[case(10, 25)]
+    fn grr_multiplication(#[case] k: u8, #[case] n: usize) {
+        let sss = SecretSharing::<POLY>(k);
+        let mut rng = ChaCha8Rng::from_seed([0x48; 32]);
+        let x = deal(&sss, &X, n, &mut rng);
+        let y = deal(&sss, &Y, n, &mut rng);
+
+        let mut z = sss.multiply_grr_rng(&x, &y, &mut rng).unwrap();
+        assert_eq!(sss.recover(&z).unwrap(), product());
+        // The product is shared with threshold k again
+        for share in z.iter_mut().take(n - k as usize) {
+            *share = None;
+        }
+        assert_eq!(sss.recover(&z).unwrap(), product());
+
+        // Products can be chained
+        let w = sss.multiply_grr_rng(
+            &x,
+            &sss.multiply_grr_rng(&x, &y, &mut rng).unwrap(),
+            &mut rng,
+        );
+        let expected: Vec<u8> = X
+            .iter()
+            .zip(product())
+            .map(|(x, p)| (GF256::<POLY>(*x) * GF256(p)).0)
+            .collect();
+        assert_eq!(sss.recover(&w.unwrap()).unwrap(), expected);
+    }
+
+    #[test]
+    fn invalid_parameters_err() {
+        let sss = SecretSharing::<POLY>(2);
+        let mut rng = ChaCha8Rng::from_seed([0x49; 32]);
+        let triples = sss.beaver_triples_rng(4, 3, &mut rng).unwrap();
+        let x = deal(&sss, &X, 3, &mut rng);
+        let y = deal(&sss, &[1, 2], 3, &mut rng);
+
+        // Not enough holders for GRR
+        let mut few = x.clone();
+        few[0] = None;
+        assert!(sss.multiply_grr_rng(&few, &few, &mut rng).is_err());
+        // Mismatched holders and lengths
+        assert!(sss.multiply_grr_rng(&few, &x, &mut rng).is_err());
+        assert!(sss.multiply_grr_rng(&x, &y, &mut rng).is_err());
+        assert!(sss.multiply_beaver(&x, &y, &triples).is_err());
+        assert!(sss.multiply_beaver(&x, &x, &triples[..2]).is_err());
+
+        assert!(sss.beaver_triples_rng(4, 1, &mut rng).is_err());
+        assert!(SecretSharing::<POLY>(129)
+            .multiply_grr_rng(&x, &x, &mut rng)
+            .is_err());
+    }
+}
@@ -0,0 +1,354 @@
+// Packed secret sharing (Franklin-Yung), sharing many secrets with a single polynomial.
+// With `t` the privacy and `k` the recovery threshold, each polynomial of degree `k - 1` carries
+// `l = k - t` secrets at the evaluation points `x = 255, 254, .., 256 - l`, and is otherwise fixed
+// by `t` random shares. Shares are the evaluations at `x = 1..=255 - l`.
+// Secrets are packed byte-wise: the bytes at the same offset of `l` secrets share one polynomial.
+// The number of secrets follows as a big endian `u32`, each byte packed `l` times in a polynomial,
+// so that recovery can check it.
+
+use alloc::vec::Vec;
+
+use super::field::GF256;
+use super::math;
+use super::share::{Share, ShareWithX};
+use super::SecretSharing;
+
+/// Length in bytes of the number of secrets at the end of each share.
+const COUNT_LENGTH: usize = 4;
+
+/// Struct which implements methods to share vectors of equally long secrets with packed Shamir's
+/// secret sharing over a 256 bits Galois Field.
+///
+/// Each polynomial packs `threshold - privacy` secrets: any `privacy` shares reveal nothing about
+/// them, and any `threshold` shares recover them. A share therefore holds one byte per packed
+/// secret byte, instead of one byte per secret byte.
+/// The maximum number of shares that can be generated is `255 - (threshold - privacy)`.
+///
+/// Usage example:
+/// ```
+/// # use ssskit::{ PackedSharing, Share };
+/// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+/// # const POLY: u16 = 0x11d_u16;
+/// // Pack 8 keys per polynomial, any 3 shares reveal nothing
+/// let packed = PackedSharing::<POLY> { privacy: 3, threshold: 11 };
+/// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+/// let keys: Vec<[u8; 32]> = (0..1000u16).map(|i| [i as u8; 32]).collect();
+/// let shares = packed
+///     .dealer_rng(&keys, &mut rng)
+///     .unwrap()
+///     .take(20)
+///     .map(Some)
+///     .collect::<Vec<Option<Share<POLY>>>>();
+/// // One byte per 8 key bytes
+/// assert_eq!(shares[0].as_ref().unwrap().y.len(), 125 * 32 + 4);
+/// let recovered = packed.recover(&shares, keys.len()).unwrap();
+/// assert_eq!(recovered[999], keys[999]);
+/// assert_eq!(packed.unpack(&shares, keys.len(), 42).unwrap(), keys[42]);
+/// ```
+pub struct PackedSharing<const POLY: u16> {
+    /// Maximum number of shares that reveal nothing about the secrets.
+    pub privacy: u8,
+    /// Minimum number of shares required to recover the secrets.
+    pub threshold: u8,
+}
+
+impl<const POLY: u16> PackedSharing<POLY> {
+    /// Number of secrets packed in each polynomial.
+    fn pack_length(&self) -> Result<usize, &'static str> {
+        if self.privacy >= self.threshold {
+            return Err("Privacy threshold must be lower than the recovery threshold");
+        }
+        Ok((self.threshold - self.privacy) as usize)
+    }
+
+    /// Given a slice of equally long `secrets`, returns an `Iterator` along new shares, each
+    /// `secrets.len().div_ceil(threshold - privacy) * secrets[0].len() + 4` bytes long, the last 4
+    /// bytes packing the number of secrets.
+    /// A random number generator has to be provided.
+    pub fn dealer_rng<S: AsRef<[u8]>, R: rand::Rng>(
+        &self,
+        secrets: &[S],
+        rng: &mut R,
+    ) -> Result<impl Iterator<Item = Share<POLY>>, &'static str> {
+        let pack_length = self.pack_length()?;
+        let secret_length = match secrets.first() {
+            Some(s) => s.as_ref().len(),
+            None => return Err("At least one secret is required"),
+        };
+        if secrets.iter().any(|s| s.as_ref().len() != secret_length) {
+            return Err("All secrets must have the same length");
+        }
+        let count = u32::try_from(secrets.len()).map_err(|_| "Too many secrets")?;
+
+        let x_samples: Vec<GF256<POLY>> = (0..pack_length)
+            .map(|j| GF256(u8::MAX - j as u8))
+            .chain((1..=self.privacy).map(GF256))
+            .collect();
+
+        let mut random = alloc::vec![0u8; self.privacy as usize];
+        let mut pack = |packed: &mut dyn Iterator<Item = u8>| {
+            rng.fill(random.as_mut_slice());
+            let y_samples: Vec<GF256<POLY>> = packed
+                .map(GF256)
+                .chain(random.iter().map(|r| GF256(*r)))
+                .collect();
+            // `get_evaluator` expects the coefficients from the highest degree down
+            let mut poly = math::interpolate_coefficients(&x_samples, &y_samples);
+            poly.reverse();
+            poly
+        };
+
+        let mut polys =
+            Vec::with_capacity(secrets.len().div_ceil(pack_length) * secret_length + COUNT_LENGTH);
+        for group in secrets.chunks(pack_length) {
+            for i in 0..secret_length {
+                polys.push(pack(
+                    &mut (0..pack_length).map(|j| group.get(j).map_or(0, |s| s.as_ref()[i])),
+                ));
+            }
+        }
+        for byte in count.to_be_bytes() {
+            polys.push(pack(&mut core::iter::repeat_n(byte, pack_length)));
+        }
+
+        #[cfg(feature = "zeroize_memory")]
+        {
+            use zeroize::Zeroize;
+            random.zeroize();
+        }
+
+        Ok(math::get_evaluator(polys).take(u8::MAX as usize - pack_length))
+    }
+
+    /// Same as `dealer_rng`, using `rand::thread_rng`.
+    #[cfg(feature = "std")]
+    pub fn dealer<S: AsRef<[u8]>>(
+        &self,
+        secrets: &[S],
+    ) -> Result<impl Iterator<Item = Share<POLY>>, &'static str> {
+        let mut rng = rand::thread_rng();
+        self.dealer_rng(secrets, &mut rng)
+    }
+
+    /// Given an iterable collection of shares and the number of secrets shared, recovers all the
+    /// original secrets. Shares are passed as in `SecretSharing::recover`, and only the first
+    /// `threshold` of them are used.
+    pub fn recover<'a, T>(&self, shares: T, count: usize) -> Result<Vec<Vec<u8>>, &'static str>
+    where
+        T: IntoIterator<Item = &'a Option<Share<POLY>>>,
+        T::IntoIter: Iterator<Item = &'a Option<Share<POLY>>>,
+    {
+        let pack_length = self.pack_length()?;
+        let (values, secret_length) = self.collect_packed(shares, count)?;
+        let x_samples: Vec<GF256<POLY>> = values.iter().map(|s| s.x.clone()).collect();
+        let weights: Vec<Vec<GF256<POLY>>> = (0..pack_length)
+            .map(|j| math::lagrange_coefficients(&x_samples, GF256(u8::MAX - j as u8)))
+            .collect();
+
+        Ok((0..count)
+            .map(|index| {
+                let offset = index / pack_length * secret_length;
+                unpack_one(
+                    &values,
+                    &weights[index % pack_length],
+                    offset,
+                    secret_length,
+                )
+            })
+            .collect())
+    }
+
+    /// Same as `recover`, but only recovers the secret at position `index`, without interpolating
+    /// the other secrets.
+    pub fn unpack<'a, T>(
+        &self,
+        shares: T,
+        count: usize,
+        index: usize,
+    ) -> Result<Vec<u8>, &'static str>
+    where
+        T: IntoIterator<Item = &'a Option<Share<POLY>>>,
+        T::IntoIter: Iterator<Item = &'a Option<Share<POLY>>>,
+    {
+        let pack_length = self.pack_length()?;
+        if index >= count {
+            return Err("Secret index is out of bounds");
+        }
+        let (values, secret_length) = self.collect_packed(shares, count)?;
+        let x_samples: Vec<GF256<POLY>> = values.iter().map(|s| s.x.clone()).collect();
+        let weights =
+            math::lagrange_coefficients(&x_samples, GF256(u8::MAX - (index % pack_length) as u8));
+
+        let offset = index / pack_length * secret_length;
+        Ok(unpack_one(&values, &weights, offset, secret_length))
+    }
+
+    /// Collects the first `threshold` shares and checks that they hold `count` packed secrets, as
+    /// recorded at the end of the shares. Returns the shares along with the length of each secret.
+    fn collect_packed<'a, T>(
+        &self,
+        shares: T,
+        count: usize,
+    ) -> Result<(Vec<ShareWithX<POLY>>, usize), &'static str>
+    where
+        T: IntoIterator<Item = &'a Option<Share<POLY>>>,
+        T::IntoIter: Iterator<Item = &'a Option<Share<POLY>>>,
+    {
+        let pack_length = self.pack_length()?;
+        let (_, mut values) = SecretSharing::<POLY>(self.threshold).collect_positioned(shares)?;
+        values.truncate(self.threshold as usize);
+
+        if values
+            .iter()
+            .any(|s| s.x.0 as usize > u8::MAX as usize - pack_length)
+        {
+            return Err("Share x coordinates must not be secret evaluation points");
+        }
+        let share_length = match values[0].y.len().checked_sub(COUNT_LENGTH) {
+            Some(length) => length,
+            None => return Err("Shares are too short to hold packed secrets"),
+        };
+        let x_samples: Vec<GF256<POLY>> = values.iter().map(|s| s.x.clone()).collect();
+        let weights = math::lagrange_coefficients(&x_samples, GF256(u8::MAX));
+        let packed_count = unpack_one(&values, &weights, share_length, COUNT_LENGTH);
+        let packed_count = u32::from_be_bytes(packed_count.try_into().unwrap());
+
+        let groups = count.div_ceil(pack_length);
+        if packed_count as usize != count || groups == 0 || share_length % groups != 0 {
+            return Err("Shares do not hold the given number of secrets");
+        }
+
+        Ok((values, share_length / groups))
+    }
+}
+
+/// Recovers the secret whose bytes are at `offset..offset + secret_length` in the shares, given the
+/// Lagrange weights of its evaluation point.
+fn unpack_one<const POLY: u16>(
+    values: &[ShareWithX<POLY>],
+    weights: &[GF256<POLY>],
+    offset: usize,
+    secret_length: usize,
+) -> Vec<u8> {
+    (offset..offset + secret_length)
+        .map(|i| {
+            weights
+                .iter()
+                .zip(values.iter())
+                .map(|(w, s)| w.clone() * s.y[i].clone())
+                .sum::<GF256<POLY>>()
+                .0
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PackedSharing;
+    use crate::Share;
+    use alloc::{vec, vec::Vec};
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    use rstest::rstest;
+
+    const POLY: u16 = 0x11d_u16;
+
+    fn make_secrets(count: usize, length: usize) -> Vec<Vec<u8>> {
+        (0..count)
+            .map(|i| (0..length).map(|j| (i * 31 + j) as u8).collect())
+            .collect()
+    }
+
+    fn make_shares(
+        packed: &PackedSharing<POLY>,
+        secrets: &[Vec<u8>],
+        n: usize,
+    ) -> Vec<Option<Share<POLY>>> {
+        let mut rng = ChaCha8Rng::from_seed([0x36; 32]);
+        packed
+            .dealer_rng(secrets, &mut rng)
+            .unwrap()
+            .take(n)
+            .map(Some)
+            .collect()
+    }
+
+    #[rstest]
+    #[case(0, 1, 1, 1, 3)]
+    #[case(2, 3, 5, 1, 3)]
+    #[case(1, 5, 9, 16, 5)]
+    #[case(3, 11, 1000, 32, 20)]
+    #[case(10, 60, 100, 2, 205)]
+    fn packed_roundtrip(
+        #[case] privacy: u8,
+        #[case] threshold: u8,
+        #[case] count: usize,
+        #[case] length: usize,
+        #[case] n: usize,
+    ) {
+        let packed = PackedSharing::<POLY> { privacy, threshold };
+        let secrets = make_secrets(count, length);
+        let mut shares = make_shares(&packed, &secrets, n);
+        let pack_length = (threshold - privacy) as usize;
+        assert!(shares
+            .iter()
+            .all(|s| s.as_ref().unwrap().y.len() == count.div_ceil(pack_length) * length + 4));
+
+        assert_eq!(packed.recover(&shares, count).unwrap(), secrets);
+        for share in shares.iter_mut().take(n - threshold as usize) {
+            *share = None;
+        }
+        assert_eq!(packed.recover(&shares, count).unwrap(), secrets);
+        for index in [0, count / 2, count - 1] {
+            assert_eq!(
+                packed.unpack(&shares, count, index).unwrap(),
+                secrets[index]
+            );
+        }
+    }
+
+    #[test]
+    fn maximum_number_of_shares() {
+        let packed = PackedSharing::<POLY> {
+            privacy: 2,
+            threshold: 6,
+        };
+        let mut rng = ChaCha8Rng::from_seed([0x36; 32]);
+        let dealer = packed.dealer_rng(&make_secrets(4, 4), &mut rng).unwrap();
+        assert_eq!(dealer.count(), 251);
+    }
+
+    #[test]
+    fn invalid_secrets_err() {
+        let packed = PackedSharing::<POLY> {
+            privacy: 1,
+            threshold: 3,
+        };
+        let mut rng = ChaCha8Rng::from_seed([0x36; 32]);
+        assert!(packed.dealer_rng::<Vec<u8>, _>(&[], &mut rng).is_err());
+        assert!(packed.dealer_rng(&[vec![1, 2], vec![3]], &mut rng).is_err());
+        assert!(PackedSharing::<POLY> {
+            privacy: 3,
+            threshold: 3
+        }
+        .dealer_rng(&[[1]], &mut rng)
+        .is_err());
+    }
+
+    #[test]
+    fn invalid_recovery_err() {
+        let packed = PackedSharing::<POLY> {
+            privacy: 1,
+            threshold: 3,
+        };
+        let shares = make_shares(&packed, &make_secrets(5, 3), 3);
+        assert!(packed.recover(&shares[..2], 5).is_err());
+        assert!(packed.recover(&shares, 0).is_err());
+        assert!(packed.recover(&shares, 7).is_err());
+        assert!(packed.unpack(&shares, 5, 5).is_err());
+        // Counts matching the share length but not the number of secrets dealt
+        assert!(packed.recover(&shares, 6).is_err());
+        assert!(packed.recover(&shares, 3).is_err());
+        assert!(packed.unpack(&shares, 6, 5).is_err());
+        assert_eq!(packed.recover(&shares, 5).unwrap().len(), 5);
+    }
+}
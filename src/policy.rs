@@ -0,0 +1,366 @@
+// General monotone access structures (Benaloh-Leichter): a policy formula of AND, OR and threshold
+// gates over named participants is shared recursively, every gate splitting its input among its
+// children with `SecretSharing`. AND gates are `n`-of-`n`, OR gates 1-of-`n` and threshold gates
+// `k`-of-`n` sharings, so the formula compiles to a `Hierarchy` whose members are its leaves.
+// A participant appearing several times in the formula holds one component share per leaf.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::hierarchy::{Hierarchy, MemberShare};
+use super::SecretSharing;
+
+/// A monotone access policy over named participants.
+///
+/// Usage example:
+/// ```
+/// # use ssskit::{ ParticipantBundle, Policy };
+/// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+/// # const POLY: u16 = 0x11d_u16;
+/// // (2 of {A, B, C}) AND (CFO OR CEO)
+/// let policy = Policy::And(vec![
+///     Policy::Threshold(2, vec!["A".into(), "B".into(), "C".into()]),
+///     Policy::Or(vec!["CFO".into(), "CEO".into()]),
+/// ]);
+/// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+/// let bundles: Vec<ParticipantBundle<POLY>> = policy.dealer_rng(&[1, 2, 3], &mut rng).unwrap();
+/// assert_eq!(bundles.len(), 5);
+/// // A, C and the CEO satisfy the policy
+/// let present = [&bundles[0], &bundles[2], &bundles[4]];
+/// assert_eq!(policy.recover(present).unwrap(), vec![1, 2, 3]);
+/// // A, B and C do not
+/// assert!(policy.recover(&bundles[..3]).is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Policy {
+    /// A single named participant.
+    Participant(String),
+    /// Satisfied when all the sub-policies are satisfied.
+    And(Vec<Policy>),
+    /// Satisfied when any of the sub-policies is satisfied.
+    Or(Vec<Policy>),
+    /// Satisfied when at least the given number of sub-policies are satisfied.
+    Threshold(u8, Vec<Policy>),
+}
+
+impl From<&str> for Policy {
+    fn from(name: &str) -> Policy {
+        Policy::Participant(name.into())
+    }
+}
+
+/// The component shares held by a participant of a policy, one per occurrence of the participant
+/// in the policy formula.
+///
+/// Can be serialized to and from a byte array as `[name length, name..., count,
+/// (share length (u16 big endian), share...)...]`, so names are limited to 255 bytes, participants
+/// to 255 occurrences in the policy and component shares to 65535 bytes. `Policy::dealer_rng`
+/// rejects policies and secrets beyond these limits.
+#[derive(Clone)]
+pub struct ParticipantBundle<const POLY: u16> {
+    /// The name of the participant.
+    pub participant: String,
+    /// The component shares of the participant.
+    pub shares: Vec<MemberShare<POLY>>,
+}
+
+impl<const POLY: u16> From<&ParticipantBundle<POLY>> for Vec<u8> {
+    fn from(b: &ParticipantBundle<POLY>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.push(b.participant.len() as u8);
+        bytes.extend_from_slice(b.participant.as_bytes());
+        bytes.push(b.shares.len() as u8);
+        for share in &b.shares {
+            let share = Vec::from(share);
+            bytes.extend_from_slice(&(share.len() as u16).to_be_bytes());
+            bytes.extend(share);
+        }
+        bytes
+    }
+}
+
+impl<const POLY: u16> core::convert::TryFrom<&[u8]> for ParticipantBundle<POLY> {
+    type Error = &'static str;
+
+    fn try_from(b: &[u8]) -> Result<ParticipantBundle<POLY>, Self::Error> {
+        const TRUNCATED: &str = "A ParticipantBundle is truncated";
+
+        let name_length = *b.first().ok_or(TRUNCATED)? as usize;
+        let name = b.get(1..1 + name_length).ok_or(TRUNCATED)?;
+        let participant = core::str::from_utf8(name)
+            .map_err(|_| "A ParticipantBundle name must be valid UTF-8")?
+            .into();
+
+        let count = *b.get(1 + name_length).ok_or(TRUNCATED)?;
+        let mut rest = &b[2 + name_length..];
+        let mut shares = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let length = rest.get(..2).ok_or(TRUNCATED)?;
+            let length = u16::from_be_bytes([length[0], length[1]]) as usize;
+            let share = rest.get(2..2 + length).ok_or(TRUNCATED)?;
+            shares.push(MemberShare::try_from(share)?);
+            rest = &rest[2 + length..];
+        }
+        if !rest.is_empty() {
+            return Err("A ParticipantBundle must not have trailing bytes");
+        }
+
+        Ok(ParticipantBundle {
+            participant,
+            shares,
+        })
+    }
+}
+
+impl Policy {
+    /// Returns whether the policy is satisfied by the given set of participants.
+    pub fn is_satisfied_by(&self, participants: &[&str]) -> bool {
+        match self {
+            Policy::Participant(name) => participants.contains(&name.as_str()),
+            Policy::And(policies) => policies.iter().all(|p| p.is_satisfied_by(participants)),
+            Policy::Or(policies) => policies.iter().any(|p| p.is_satisfied_by(participants)),
+            Policy::Threshold(threshold, policies) => {
+                policies
+                    .iter()
+                    .filter(|p| p.is_satisfied_by(participants))
+                    .count()
+                    >= *threshold as usize
+            }
+        }
+    }
+
+    /// Compiles the policy into the threshold and the members of the top level group of a
+    /// sharing hierarchy.
+    fn to_hierarchy(&self) -> Result<(u8, Vec<Hierarchy>), &'static str> {
+        match self.to_node()? {
+            Hierarchy::Group { threshold, members } => Ok((threshold, members)),
+            member => Ok((1, alloc::vec![member])),
+        }
+    }
+
+    fn to_node(&self) -> Result<Hierarchy, &'static str> {
+        let policies = match self {
+            Policy::Participant(name) if name.len() > u8::MAX as usize => {
+                return Err("Participant names must be at most 255 bytes long")
+            }
+            Policy::Participant(_) => return Ok(Hierarchy::Member),
+            Policy::And(policies) | Policy::Or(policies) | Policy::Threshold(_, policies) => {
+                policies
+            }
+        };
+        if policies.is_empty() || policies.len() > u8::MAX as usize {
+            return Err("Policy gates must have between 1 and 255 sub-policies");
+        }
+        let threshold = match self {
+            Policy::Or(_) => 1,
+            Policy::Threshold(threshold, _) => *threshold,
+            _ => policies.len() as u8,
+        };
+        Ok(Hierarchy::Group {
+            threshold,
+            members: policies
+                .iter()
+                .map(|p| p.to_node())
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Returns the participant names of the policy leaves, in depth-first order.
+    fn leaves<'a>(&'a self, names: &mut Vec<&'a str>) {
+        match self {
+            Policy::Participant(name) => names.push(name),
+            Policy::And(policies) | Policy::Or(policies) | Policy::Threshold(_, policies) => {
+                policies.iter().for_each(|p| p.leaves(names))
+            }
+        }
+    }
+
+    /// Given a `secret` byte slice, returns the bundle of component shares of every participant of
+    /// the policy, in order of first appearance in the formula.
+    /// A random number generator has to be provided.
+    pub fn dealer_rng<const POLY: u16, R: rand::Rng>(
+        &self,
+        secret: &[u8],
+        rng: &mut R,
+    ) -> Result<Vec<ParticipantBundle<POLY>>, &'static str> {
+        let (threshold, members) = self.to_hierarchy()?;
+        let shares =
+            SecretSharing::<POLY>(threshold).dealer_hierarchical_rng(secret, &members, rng)?;
+
+        let mut names = Vec::new();
+        self.leaves(&mut names);
+        let mut bundles: Vec<ParticipantBundle<POLY>> = Vec::new();
+        for (name, share) in names.into_iter().zip(shares) {
+            if Vec::from(&share).len() > u16::MAX as usize {
+                return Err("Component shares must be at most 65535 bytes long");
+            }
+            match bundles.iter_mut().find(|b| b.participant == name) {
+                Some(bundle) => bundle.shares.push(share),
+                None => bundles.push(ParticipantBundle {
+                    participant: name.into(),
+                    shares: alloc::vec![share],
+                }),
+            }
+        }
+        if bundles.iter().any(|b| b.shares.len() > u8::MAX as usize) {
+            return Err("Participants must appear at most 255 times in the policy");
+        }
+        Ok(bundles)
+    }
+
+    /// Same as `dealer_rng`, using `rand::thread_rng`.
+    #[cfg(feature = "std")]
+    pub fn dealer<const POLY: u16>(
+        &self,
+        secret: &[u8],
+    ) -> Result<Vec<ParticipantBundle<POLY>>, &'static str> {
+        let mut rng = rand::thread_rng();
+        self.dealer_rng(secret, &mut rng)
+    }
+
+    /// Given an iterable collection of participant bundles, recovers the original secret.
+    /// Succeeds exactly when the presented participants satisfy the policy, otherwise an `Err`
+    /// is returned.
+    pub fn recover<'a, const POLY: u16, T>(&self, bundles: T) -> Result<Vec<u8>, &'static str>
+    where
+        T: IntoIterator<Item = &'a ParticipantBundle<POLY>>,
+        T::IntoIter: Iterator<Item = &'a ParticipantBundle<POLY>>,
+    {
+        let (threshold, members) = self.to_hierarchy()?;
+        SecretSharing::<POLY>(threshold)
+            .recover_hierarchical(&members, bundles.into_iter().flat_map(|b| b.shares.iter()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ParticipantBundle, Policy};
+    use alloc::{string::String, vec, vec::Vec};
+    use core::convert::TryFrom;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+
+    const POLY: u16 = 0x11d_u16;
+
+    fn make_bundles(policy: &Policy) -> Vec<ParticipantBundle<POLY>> {
+        let mut rng = ChaCha8Rng::from_seed([0x39; 32]);
+        policy.dealer_rng(&[3, 1, 4, 1, 5], &mut rng).unwrap()
+    }
+
+    fn policies() -> Vec<Policy> {
+        vec![
+            "A".into(),
+            Policy::Or(vec!["A".into(), "B".into()]),
+            Policy::And(vec![
+                Policy::Threshold(2, vec!["A".into(), "B".into(), "C".into()]),
+                Policy::Or(vec!["CFO".into(), "CEO".into()]),
+            ]),
+            // Participants appearing several times
+            Policy::Or(vec![
+                Policy::And(vec!["A".into(), "B".into()]),
+                Policy::And(vec!["A".into(), "C".into(), "D".into()]),
+                Policy::Threshold(
+                    2,
+                    vec![
+                        "B".into(),
+                        Policy::And(vec!["C".into(), "D".into()]),
+                        "E".into(),
+                    ],
+                ),
+            ]),
+        ]
+    }
+
+    #[test]
+    fn recovers_exactly_when_satisfied() {
+        for policy in policies() {
+            let bundles = make_bundles(&policy);
+            // Every subset of participants
+            for subset in 0..1u32 << bundles.len() {
+                let present: Vec<&ParticipantBundle<POLY>> = (0..bundles.len())
+                    .filter(|i| subset & (1 << i) != 0)
+                    .map(|i| &bundles[i])
+                    .collect();
+                let names: Vec<&str> = present.iter().map(|b| b.participant.as_str()).collect();
+
+                let result = policy.recover(present);
+                if policy.is_satisfied_by(&names) {
+                    assert_eq!(result.unwrap(), vec![3, 1, 4, 1, 5]);
+                } else {
+                    assert!(result.is_err());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bundles_group_repeated_participants() {
+        let policy = &policies()[3];
+        let bundles = make_bundles(policy);
+        let names: Vec<&str> = bundles.iter().map(|b| b.participant.as_str()).collect();
+        assert_eq!(names, vec!["A", "B", "C", "D", "E"]);
+        let counts: Vec<usize> = bundles.iter().map(|b| b.shares.len()).collect();
+        assert_eq!(counts, vec![2, 2, 2, 2, 1]);
+    }
+
+    #[test]
+    fn serialization_roundtrip() {
+        let policy = &policies()[3];
+        let bundles: Vec<ParticipantBundle<POLY>> = make_bundles(policy)
+            .iter()
+            .map(|b| ParticipantBundle::try_from(Vec::from(b).as_slice()).unwrap())
+            .collect();
+        assert_eq!(bundles[2].participant, "C");
+        assert_eq!(policy.recover(&bundles[1..4]).unwrap(), vec![3, 1, 4, 1, 5]);
+
+        let bytes = Vec::from(&bundles[0]);
+        assert!(ParticipantBundle::<POLY>::try_from(&bytes[..bytes.len() - 1]).is_err());
+        assert!(
+            ParticipantBundle::<POLY>::try_from(&[bytes.as_slice(), &[0]].concat()[..]).is_err()
+        );
+        assert!(ParticipantBundle::<POLY>::try_from(&[1u8, 0xff, 0][..]).is_err());
+    }
+
+    #[test]
+    fn invalid_policies_err() {
+        let mut rng = ChaCha8Rng::from_seed([0x39; 32]);
+        for policy in [
+            Policy::And(vec![]),
+            Policy::Threshold(0, vec!["A".into()]),
+            Policy::Threshold(3, vec!["A".into(), "B".into()]),
+        ] {
+            assert!(policy.dealer_rng::<POLY, _>(&[1, 2], &mut rng).is_err());
+        }
+    }
+
+    #[test]
+    fn unserializable_sizes_err() {
+        let mut rng = ChaCha8Rng::from_seed([0x39; 32]);
+        let name: String = "A".repeat(256);
+        let policies = [
+            // A participant name over 255 bytes
+            Policy::Or(vec![name.as_str().into(), "B".into()]),
+            // A participant appearing 256 times
+            Policy::And(vec![
+                Policy::Or(vec!["A".into(); 200]),
+                Policy::Or(vec!["A".into(); 56]),
+            ]),
+            // A gate with 256 sub-policies
+            Policy::Or(vec!["A".into(); 256]),
+        ];
+        for policy in policies {
+            assert!(policy.dealer_rng::<POLY, _>(&[1, 2], &mut rng).is_err());
+        }
+        let name: String = "A".repeat(255);
+        let policy = Policy::Or(vec![name.as_str().into(), "B".into()]);
+        let bundles: Vec<ParticipantBundle<POLY>> = policy.dealer_rng(&[1, 2], &mut rng).unwrap();
+        let bundle =
+            ParticipantBundle::<POLY>::try_from(Vec::from(&bundles[0]).as_slice()).unwrap();
+        assert_eq!(bundle.participant, name);
+
+        // Component shares over 65535 bytes
+        let secret = vec![0; u16::MAX as usize];
+        assert!(Policy::from("A")
+            .dealer_rng::<POLY, _>(&secret, &mut rng)
+            .is_err());
+    }
+}
@@ -0,0 +1,232 @@
+// Ramp secret sharing, trading privacy for smaller shares.
+// Every polynomial of degree `k - 1` embeds `k - t` secret bytes as its lowest coefficients, masked
+// by `t` random higher coefficients. Any `t` shares reveal nothing about the secret, any `k` shares
+// recover it, and in between partial information may leak.
+
+use alloc::vec::Vec;
+
+use super::field::GF256;
+use super::math;
+use super::share::Share;
+use super::SecretSharing;
+
+/// Marker byte appended to the secret before padding it with zeros to a multiple of `k - t` bytes.
+const PADDING_MARKER: u8 = 0x80;
+
+/// Struct which implements methods to generate shares and recover secrets with a `(t, k, n)` ramp
+/// scheme over a 256 bits Galois Field.
+///
+/// Any `privacy` shares reveal nothing about the secret, and any `threshold` shares recover it.
+/// Each share is about `|secret| / (threshold - privacy)` bytes long, so lowering `privacy` below
+/// `threshold - 1` shrinks the shares of large secrets.
+/// With `privacy = threshold - 1` this is plain Shamir's secret sharing plus a padding byte.
+///
+/// Usage example:
+/// ```
+/// # use ssskit::{ RampSharing, Share };
+/// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+/// # const POLY: u16 = 0x11d_u16;
+/// // Any 2 shares reveal nothing, any 5 recover the secret
+/// let ramp = RampSharing::<POLY> { privacy: 2, threshold: 5 };
+/// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+/// let secret = vec![42u8; 299];
+/// let shares = ramp
+///     .dealer_rng(&secret, &mut rng)
+///     .unwrap()
+///     .take(7)
+///     .map(Some)
+///     .collect::<Vec<Option<Share<POLY>>>>();
+/// // Each share is a third of the secret, plus padding
+/// assert_eq!(shares[0].as_ref().unwrap().y.len(), 100);
+/// assert_eq!(ramp.recover(&shares).unwrap(), secret);
+/// ```
+pub struct RampSharing<const POLY: u16> {
+    /// Maximum number of shares that reveal nothing about the secret.
+    pub privacy: u8,
+    /// Minimum number of shares required to recover the secret.
+    pub threshold: u8,
+}
+
+impl<const POLY: u16> RampSharing<POLY> {
+    /// Number of secret bytes embedded in each polynomial.
+    fn block_length(&self) -> Result<usize, &'static str> {
+        if self.privacy >= self.threshold {
+            return Err("Privacy threshold must be lower than the recovery threshold");
+        }
+        Ok((self.threshold - self.privacy) as usize)
+    }
+
+    /// Given a `secret` byte slice, returns an `Iterator` along new shares, each
+    /// `(|secret| + 1).div_ceil(threshold - privacy)` bytes long.
+    /// The maximum number of shares that can be generated is 255.
+    /// A random number generator has to be provided.
+    pub fn dealer_rng<R: rand::Rng>(
+        &self,
+        secret: &[u8],
+        rng: &mut R,
+    ) -> Result<impl Iterator<Item = Share<POLY>>, &'static str> {
+        let block_length = self.block_length()?;
+
+        let mut padded = Vec::with_capacity((secret.len() + 1).div_ceil(block_length));
+        padded.extend_from_slice(secret);
+        padded.push(PADDING_MARKER);
+        padded.resize(padded.len().div_ceil(block_length) * block_length, 0);
+
+        // `random_polynomial` returns the coefficients from the highest degree down, ending with the
+        // given constant, so the lower secret bytes are appended in reverse order after it.
+        let mut polys = Vec::with_capacity(padded.len() / block_length);
+        for block in padded.chunks_exact(block_length) {
+            let mut poly =
+                math::random_polynomial(GF256(block[block_length - 1]), self.privacy + 1, rng);
+            poly.extend(block[..block_length - 1].iter().rev().map(|b| GF256(*b)));
+            polys.push(poly);
+        }
+
+        #[cfg(feature = "zeroize_memory")]
+        {
+            use zeroize::Zeroize;
+            padded.zeroize();
+        }
+
+        Ok(math::get_evaluator(polys))
+    }
+
+    /// Same as `dealer_rng`, using `rand::thread_rng`.
+    #[cfg(feature = "std")]
+    pub fn dealer(&self, secret: &[u8]) -> Result<impl Iterator<Item = Share<POLY>>, &'static str> {
+        let mut rng = rand::thread_rng();
+        self.dealer_rng(secret, &mut rng)
+    }
+
+    /// Given an iterable collection of shares, recovers the original secret.
+    /// Shares are passed as in `SecretSharing::recover`, and only the first `threshold` of them
+    /// are used. An `Err` is returned if there are not enough shares or the padding is invalid,
+    /// which happens when the shares are corrupted or the parameters are wrong.
+    ///
+    /// Example:
+    /// ```
+    /// # use ssskit::{ RampSharing, Share };
+    /// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    /// # const POLY: u16 = 0x11d_u16;
+    /// # let ramp = RampSharing::<POLY> { privacy: 1, threshold: 3 };
+    /// # let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// let mut shares = ramp
+    ///     .dealer_rng(&[1, 2, 3, 4], &mut rng)
+    ///     .unwrap()
+    ///     .take(4)
+    ///     .map(Some)
+    ///     .collect::<Vec<Option<Share<POLY>>>>();
+    /// shares[0] = None;
+    /// assert_eq!(ramp.recover(&shares).unwrap(), vec![1, 2, 3, 4]);
+    /// shares[1] = None;
+    /// assert!(ramp.recover(&shares).is_err());
+    /// ```
+    pub fn recover<'a, T>(&self, shares: T) -> Result<Vec<u8>, &'static str>
+    where
+        T: IntoIterator<Item = &'a Option<Share<POLY>>>,
+        T::IntoIter: Iterator<Item = &'a Option<Share<POLY>>>,
+    {
+        let block_length = self.block_length()?;
+        let (_, mut values) = SecretSharing::<POLY>(self.threshold).collect_positioned(shares)?;
+        values.truncate(self.threshold as usize);
+
+        let x_samples: Vec<GF256<POLY>> = values.iter().map(|s| s.x.clone()).collect();
+        let mut secret = Vec::with_capacity(values[0].y.len() * block_length);
+        for i in 0..values[0].y.len() {
+            let y_samples: Vec<GF256<POLY>> = values.iter().map(|s| s.y[i].clone()).collect();
+            let poly = math::interpolate_coefficients(&x_samples, &y_samples);
+            secret.extend(poly[..block_length].iter().map(|c| c.0));
+        }
+
+        match secret.iter().rposition(|b| *b != 0) {
+            Some(i) if secret[i] == PADDING_MARKER => {
+                secret.truncate(i);
+                Ok(secret)
+            }
+            _ => Err("Invalid secret padding, shares are corrupted or the parameters are wrong"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RampSharing;
+    use crate::Share;
+    use alloc::vec::Vec;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    use rstest::rstest;
+
+    const POLY: u16 = 0x11d_u16;
+
+    fn make_shares(ramp: &RampSharing<POLY>, secret: &[u8], n: usize) -> Vec<Option<Share<POLY>>> {
+        let mut rng = ChaCha8Rng::from_seed([0x35; 32]);
+        ramp.dealer_rng(secret, &mut rng)
+            .unwrap()
+            .take(n)
+            .map(Some)
+            .collect()
+    }
+
+    #[rstest]
+    #[case(0, 1, 3, 10)]
+    #[case(2, 3, 3, 0)]
+    #[case(1, 4, 6, 1)]
+    #[case(2, 5, 7, 299)]
+    #[case(10, 200, 255, 10_000)]
+    fn ramp_roundtrip(
+        #[case] privacy: u8,
+        #[case] threshold: u8,
+        #[case] n: usize,
+        #[case] length: usize,
+    ) {
+        let ramp = RampSharing::<POLY> { privacy, threshold };
+        let secret: Vec<u8> = (0..length).map(|i| (i % 256) as u8).collect();
+        let mut shares = make_shares(&ramp, &secret, n);
+        let block_length = (threshold - privacy) as usize;
+        assert!(shares
+            .iter()
+            .all(|s| s.as_ref().unwrap().y.len() == (length + 1).div_ceil(block_length)));
+
+        assert_eq!(ramp.recover(&shares).unwrap(), secret);
+        for share in shares.iter_mut().take(n - threshold as usize) {
+            *share = None;
+        }
+        assert_eq!(ramp.recover(&shares).unwrap(), secret);
+    }
+
+    #[test]
+    fn trailing_zeros_are_kept() {
+        let ramp = RampSharing::<POLY> {
+            privacy: 1,
+            threshold: 3,
+        };
+        let secret = [0x80, 0, 0, 0x80, 0];
+        assert_eq!(
+            ramp.recover(&make_shares(&ramp, &secret, 3)).unwrap(),
+            secret
+        );
+    }
+
+    #[test]
+    fn invalid_parameters_err() {
+        let mut rng = ChaCha8Rng::from_seed([0x35; 32]);
+        for (privacy, threshold) in [(3, 3), (4, 3), (0, 0)] {
+            let ramp = RampSharing::<POLY> { privacy, threshold };
+            assert!(ramp.dealer_rng(&[1, 2, 3], &mut rng).is_err());
+            assert!(ramp.recover(&[]).is_err());
+        }
+    }
+
+    #[test]
+    fn insufficient_or_corrupted_shares_err() {
+        let ramp = RampSharing::<POLY> {
+            privacy: 2,
+            threshold: 4,
+        };
+        let mut shares = make_shares(&ramp, &[7; 20], 4);
+        assert!(ramp.recover(&shares[..3]).is_err());
+
+        shares[3].as_mut().unwrap().y[10].0 ^= 0x33;
+        assert!(ramp.recover(&shares).is_err());
+    }
+}
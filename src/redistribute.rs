@@ -0,0 +1,290 @@
+// Secret redistribution (Desmedt-Jajodia, Wong-Wang-Wing): moves a secret from a `(k, n)` committee
+// to a new `(k', n')` committee without reconstructing it. `k` old holders each share their own
+// share with the new threshold `k'` among the new holders, who combine the sub-shares they receive
+// with the Lagrange weights of the old holders at `x = 0`.
+
+use alloc::vec::Vec;
+use hashbrown::HashSet;
+
+#[cfg(feature = "zeroize_memory")]
+use zeroize::Zeroize;
+
+use super::field::GF256;
+use super::math;
+use super::share::{new_share, Share};
+use super::SecretSharing;
+
+/// A sub-share of the share of the old holder at `from`, sent to the new holder at `to`.
+#[derive(Clone)]
+#[cfg_attr(feature = "zeroize_memory", derive(Zeroize))]
+#[cfg_attr(feature = "zeroize_memory", zeroize(drop))]
+pub struct RedistributionMessage<const POLY: u16> {
+    /// The x coordinate of the sending old holder.
+    pub from: u8,
+    /// The x coordinate of the receiving new holder.
+    pub to: u8,
+    /// The sub-share, i.e. the sharing polynomials of the old share evaluated at `to`.
+    pub y: Vec<GF256<POLY>>,
+}
+
+impl<const POLY: u16> SecretSharing<POLY> {
+    /// First round of a redistribution, run by the old holder at `from` on the new sharing: shares
+    /// `share` with the new threshold among the new holders at `to` and returns their sub-shares.
+    /// A random number generator has to be provided.
+    pub fn redistribution_messages_rng<R: rand::Rng>(
+        &self,
+        from: u8,
+        share: &Share<POLY>,
+        to: &[u8],
+        rng: &mut R,
+    ) -> Result<Vec<RedistributionMessage<POLY>>, &'static str> {
+        if self.0 == 0 {
+            return Err("A threshold of at least 1 is required");
+        }
+        if to.contains(&0) {
+            return Err("Holders x coordinates must not be 0");
+        }
+
+        let polys: Vec<Vec<GF256<POLY>>> = share
+            .y
+            .iter()
+            .map(|y| math::random_polynomial(y.clone(), self.0, rng))
+            .collect();
+        let messages = to
+            .iter()
+            .map(|&to| RedistributionMessage {
+                from,
+                to,
+                y: polys
+                    .iter()
+                    .map(|p| {
+                        p.iter()
+                            .fold(GF256(0), |acc, c| acc * GF256(to) + c.clone())
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        #[cfg(feature = "zeroize_memory")]
+        {
+            let mut polys = polys;
+            polys.zeroize();
+        }
+
+        Ok(messages)
+    }
+
+    /// Same as `redistribution_messages_rng`, using `rand::thread_rng`.
+    #[cfg(feature = "std")]
+    pub fn redistribution_messages(
+        &self,
+        from: u8,
+        share: &Share<POLY>,
+        to: &[u8],
+    ) -> Result<Vec<RedistributionMessage<POLY>>, &'static str> {
+        let mut rng = rand::thread_rng();
+        self.redistribution_messages_rng(from, share, to, &mut rng)
+    }
+
+    /// Second round of a redistribution, run by the new holder at `to` on the old sharing: combines
+    /// the sub-shares received from the first `k` distinct old holders into its new share.
+    /// Without `share_x`, the new share must be placed at position `to - 1`.
+    pub fn combine_redistribution<'a, T>(
+        &self,
+        to: u8,
+        messages: T,
+    ) -> Result<Share<POLY>, &'static str>
+    where
+        T: IntoIterator<Item = &'a RedistributionMessage<POLY>>,
+        T::IntoIter: Iterator<Item = &'a RedistributionMessage<POLY>>,
+    {
+        let mut senders = HashSet::new();
+        let messages: Vec<&RedistributionMessage<POLY>> =
+            messages.into_iter().take(self.0 as usize).collect();
+        if messages.is_empty() || messages.len() < self.0 as usize {
+            return Err("Not enough redistribution messages to combine a share");
+        }
+        if !messages
+            .iter()
+            .all(|m| m.to == to && m.from != 0 && senders.insert(m.from))
+        {
+            return Err(
+                "Redistribution messages must be addressed to the holder and from distinct holders",
+            );
+        }
+        if messages.iter().any(|m| m.y.len() != messages[0].y.len()) {
+            return Err("All redistribution messages must have the same length");
+        }
+
+        let x_samples: Vec<GF256<POLY>> = messages.iter().map(|m| GF256(m.from)).collect();
+        let weights = math::lagrange_coefficients(&x_samples, GF256(0));
+        let y = (0..messages[0].y.len())
+            .map(|i| {
+                weights
+                    .iter()
+                    .zip(messages.iter())
+                    .map(|(w, m)| w.clone() * m.y[i].clone())
+                    .sum()
+            })
+            .collect();
+        Ok(new_share(GF256(to), y))
+    }
+
+    /// Runs a whole redistribution in-process, as each holder would in the protocol: the first `k`
+    /// known old shares are redistributed to `n` new holders of the `new` sharing, at `x = 1..=n`.
+    /// Shares are passed as in `recover`.
+    ///
+    /// Example:
+    /// ```
+    /// # use ssskit::{ SecretSharing, Share };
+    /// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    /// # const POLY: u16 = 0x11d_u16;
+    /// let old = SecretSharing::<POLY>(2);
+    /// let new = SecretSharing::<POLY>(4);
+    /// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// let shares = old
+    ///     .dealer_rng(&[1, 2, 3], &mut rng)
+    ///     .take(3)
+    ///     .map(Some)
+    ///     .collect::<Vec<Option<Share<POLY>>>>();
+    /// // Move from a 2-of-3 to a 4-of-6 committee
+    /// let new_shares = old.redistribute_rng(&new, &shares, 6, &mut rng).unwrap();
+    /// let mut new_shares = new_shares.into_iter().map(Some).collect::<Vec<_>>();
+    /// assert_eq!(new.recover(&new_shares).unwrap(), vec![1, 2, 3]);
+    /// new_shares[0] = None;
+    /// new_shares[1] = None;
+    /// new_shares[2] = None;
+    /// assert!(new.recover(&new_shares).is_err());
+    /// ```
+    pub fn redistribute_rng<R: rand::Rng>(
+        &self,
+        new: &SecretSharing<POLY>,
+        shares: &[Option<Share<POLY>>],
+        n: u8,
+        rng: &mut R,
+    ) -> Result<Vec<Share<POLY>>, &'static str> {
+        let (positions, values) = self.collect_positioned(shares)?;
+        let holders: Vec<u8> = (1..=n).collect();
+
+        let mut messages = Vec::with_capacity(self.0 as usize * n as usize);
+        for (&i, value) in positions.iter().zip(values.iter()).take(self.0 as usize) {
+            let share = shares[i].as_ref().unwrap();
+            messages.extend(new.redistribution_messages_rng(value.x.0, share, &holders, rng)?);
+        }
+        holders
+            .iter()
+            .map(|&to| self.combine_redistribution(to, messages.iter().filter(|m| m.to == to)))
+            .collect()
+    }
+
+    /// Same as `redistribute_rng`, using `rand::thread_rng`.
+    #[cfg(feature = "std")]
+    pub fn redistribute(
+        &self,
+        new: &SecretSharing<POLY>,
+        shares: &[Option<Share<POLY>>],
+        n: u8,
+    ) -> Result<Vec<Share<POLY>>, &'static str> {
+        let mut rng = rand::thread_rng();
+        self.redistribute_rng(new, shares, n, &mut rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{SecretSharing, Share};
+    use alloc::{vec, vec::Vec};
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    use rstest::rstest;
+
+    const POLY: u16 = 0x11d_u16;
+
+    fn make_shares(k: u8, secret: &[u8], n: usize) -> Vec<Option<Share<POLY>>> {
+        let mut rng = ChaCha8Rng::from_seed([0x41; 32]);
+        SecretSharing::<POLY>(k)
+            .dealer_rng(secret, &mut rng)
+            .take(n)
+            .map(Some)
+            .collect()
+    }
+
+    #[rstest]
+    #[case(3, 5, 2, 4)]
+    #[case(2, 3, 4, 7)]
+    #[case(1, 1, 1, 3)]
+    #[case(5, 5, 5, 5)]
+    #[case(10, 20, 30, 255)]
+    fn redistribution_keeps_secret(
+        #[case] k: u8,
+        #[case] n: usize,
+        #[case] new_k: u8,
+        #[case] new_n: u8,
+    ) {
+        let old = SecretSharing::<POLY>(k);
+        let new = SecretSharing::<POLY>(new_k);
+        let secret = [7, 7, 8, 9];
+        let mut shares = make_shares(k, &secret, n);
+        // Only the last k old holders take part
+        for share in shares.iter_mut().take(n - k as usize) {
+            *share = None;
+        }
+
+        let mut rng = ChaCha8Rng::from_seed([0x42; 32]);
+        let new_shares = old
+            .redistribute_rng(&new, &shares, new_n, &mut rng)
+            .unwrap();
+        assert_eq!(new_shares.len(), new_n as usize);
+
+        let mut new_shares: Vec<Option<Share<POLY>>> = new_shares.into_iter().map(Some).collect();
+        assert_eq!(new.recover(&new_shares).unwrap(), secret);
+        for share in new_shares.iter_mut().take((new_n - new_k) as usize) {
+            *share = None;
+        }
+        assert_eq!(new.recover(&new_shares).unwrap(), secret);
+    }
+
+    #[test]
+    fn new_threshold_is_enforced() {
+        let old = SecretSharing::<POLY>(2);
+        let new = SecretSharing::<POLY>(3);
+        let shares = make_shares(2, &[1, 2, 3], 2);
+        let mut rng = ChaCha8Rng::from_seed([0x42; 32]);
+        let new_shares: Vec<Option<Share<POLY>>> = old
+            .redistribute_rng(&new, &shares, 3, &mut rng)
+            .unwrap()
+            .into_iter()
+            .map(Some)
+            .collect();
+        // Two new shares look like a valid 2-of-n sharing of another secret
+        assert_ne!(old.recover(&new_shares[..2]).unwrap(), vec![1, 2, 3]);
+        assert_eq!(new.recover(&new_shares).unwrap(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn invalid_messages_err() {
+        let old = SecretSharing::<POLY>(2);
+        let new = SecretSharing::<POLY>(2);
+        let shares = make_shares(2, &[1, 2, 3], 2);
+        let mut rng = ChaCha8Rng::from_seed([0x42; 32]);
+        let share = shares[0].as_ref().unwrap();
+        let messages = new
+            .redistribution_messages_rng(1, share, &[1, 2], &mut rng)
+            .unwrap();
+
+        // Not enough messages
+        assert!(old.combine_redistribution(1, &messages[..1]).is_err());
+        // Addressed to different holders
+        assert!(old.combine_redistribution(1, &messages).is_err());
+        // Duplicated sender
+        assert!(old
+            .combine_redistribution(1, [&messages[0], &messages[0]])
+            .is_err());
+
+        assert!(new
+            .redistribution_messages_rng(1, share, &[0], &mut rng)
+            .is_err());
+        assert!(old
+            .redistribute_rng(&new, &shares[..1], 3, &mut rng)
+            .is_err());
+    }
+}
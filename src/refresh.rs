@@ -0,0 +1,270 @@
+// Proactive share refresh (Herzberg et al.): every holder deals a random sharing of zero, one
+// polynomial with a zero constant term per secret byte, and sends a sub-share to every holder.
+// Each holder adds the sub-shares it receives to its share, which yields a fresh sharing of the
+// same secret without reconstructing it. Old and refreshed shares cannot be combined.
+
+use alloc::vec::Vec;
+use hashbrown::HashSet;
+
+#[cfg(feature = "zeroize_memory")]
+use zeroize::Zeroize;
+
+use super::field::GF256;
+use super::math;
+use super::share::{share_x, Share};
+use super::SecretSharing;
+
+/// A sub-share sent by the holder at `from` to the holder at `to` during a refresh round.
+#[derive(Clone)]
+#[cfg_attr(feature = "zeroize_memory", derive(Zeroize))]
+#[cfg_attr(feature = "zeroize_memory", zeroize(drop))]
+pub struct RefreshMessage<const POLY: u16> {
+    /// The x coordinate of the sending holder.
+    pub from: u8,
+    /// The x coordinate of the receiving holder.
+    pub to: u8,
+    /// The sub-share, i.e. the zero sharing polynomials evaluated at `to`.
+    pub y: Vec<GF256<POLY>>,
+}
+
+impl<const POLY: u16> SecretSharing<POLY> {
+    /// First round of a refresh, run by the holder at `from`: returns the sub-shares of a random
+    /// sharing of zero for a secret of `length` bytes, one for every holder in `holders` (including
+    /// itself). A random number generator has to be provided.
+    pub fn refresh_messages_rng<R: rand::Rng>(
+        &self,
+        from: u8,
+        length: usize,
+        holders: &[u8],
+        rng: &mut R,
+    ) -> Result<Vec<RefreshMessage<POLY>>, &'static str> {
+        if self.0 == 0 {
+            return Err("A threshold of at least 1 is required");
+        }
+        if holders.contains(&0) {
+            return Err("Holders x coordinates must not be 0");
+        }
+
+        let polys: Vec<Vec<GF256<POLY>>> = (0..length)
+            .map(|_| math::random_polynomial(GF256(0), self.0, rng))
+            .collect();
+        let messages = holders
+            .iter()
+            .map(|&to| RefreshMessage {
+                from,
+                to,
+                y: polys
+                    .iter()
+                    .map(|p| {
+                        p.iter()
+                            .fold(GF256(0), |acc, c| acc * GF256(to) + c.clone())
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        #[cfg(feature = "zeroize_memory")]
+        {
+            let mut polys = polys;
+            polys.zeroize();
+        }
+
+        Ok(messages)
+    }
+
+    /// Same as `refresh_messages_rng`, using `rand::thread_rng`.
+    #[cfg(feature = "std")]
+    pub fn refresh_messages(
+        &self,
+        from: u8,
+        length: usize,
+        holders: &[u8],
+    ) -> Result<Vec<RefreshMessage<POLY>>, &'static str> {
+        let mut rng = rand::thread_rng();
+        self.refresh_messages_rng(from, length, holders, &mut rng)
+    }
+
+    /// Second round of a refresh, run by the holder at `x`: adds the sub-shares received from every
+    /// holder in `holders` to `share`. There must be exactly one message from each of the holders,
+    /// addressed to `x` and matching the share length, otherwise an `Err` is returned and the share
+    /// is left unchanged: a share refreshed with only some of the messages would no longer be on
+    /// the same polynomial as the others.
+    pub fn apply_refresh<'a, T>(
+        &self,
+        x: u8,
+        share: &mut Share<POLY>,
+        holders: &[u8],
+        messages: T,
+    ) -> Result<(), &'static str>
+    where
+        T: IntoIterator<Item = &'a RefreshMessage<POLY>>,
+        T::IntoIter: Iterator<Item = &'a RefreshMessage<POLY>>,
+    {
+        let messages: Vec<&RefreshMessage<POLY>> = messages.into_iter().collect();
+        let mut senders = HashSet::new();
+        if !messages.iter().all(|m| m.to == x && senders.insert(m.from)) {
+            return Err(
+                "Refresh messages must be addressed to the holder and from distinct holders",
+            );
+        }
+        let expected: HashSet<u8> = holders.iter().copied().collect();
+        if holders.is_empty() || expected.len() != holders.len() || senders != expected {
+            return Err(
+                "Refresh messages must come from each of the distinct holders exactly once",
+            );
+        }
+        if messages.iter().any(|m| m.y.len() != share.y.len()) {
+            return Err("Refresh messages must have the same length as the share");
+        }
+
+        for message in messages {
+            for (y, d) in share.y.iter_mut().zip(message.y.iter()) {
+                *y = y.clone() + d.clone();
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs a whole refresh round in-process over the known shares, as each holder would in the
+    /// protocol: every holder sends its refresh messages to every other and applies the ones it
+    /// receives. Shares are passed as in `recover`.
+    ///
+    /// Example:
+    /// ```
+    /// # use ssskit::{ SecretSharing, Share };
+    /// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    /// # const POLY: u16 = 0x11d_u16;
+    /// # let sss = SecretSharing::<POLY>(3);
+    /// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// let old = sss
+    ///     .dealer_rng(&[1, 2, 3], &mut rng)
+    ///     .take(5)
+    ///     .map(Some)
+    ///     .collect::<Vec<Option<Share<POLY>>>>();
+    /// let mut new = old.clone();
+    /// sss.refresh_rng(&mut new, &mut rng).unwrap();
+    /// assert_eq!(sss.recover(&new).unwrap(), vec![1, 2, 3]);
+    /// // Old shares are useless along with refreshed ones
+    /// let mixed = [old[0].clone(), old[1].clone(), new[2].clone()];
+    /// assert_ne!(sss.recover(&mixed).unwrap(), vec![1, 2, 3]);
+    /// ```
+    pub fn refresh_rng<R: rand::Rng>(
+        &self,
+        shares: &mut [Option<Share<POLY>>],
+        rng: &mut R,
+    ) -> Result<(), &'static str> {
+        let (positions, values) = self.collect_positioned(shares.iter())?;
+        let holders: Vec<u8> = values.iter().map(|s| s.x.0).collect();
+        let length = values[0].y.len();
+
+        let mut messages = Vec::with_capacity(holders.len() * holders.len());
+        for &from in &holders {
+            messages.extend(self.refresh_messages_rng(from, length, &holders, rng)?);
+        }
+        for &i in &positions {
+            let share = shares[i].as_mut().unwrap();
+            let x = share_x(i, share);
+            self.apply_refresh(x, share, &holders, messages.iter().filter(|m| m.to == x))?;
+        }
+        Ok(())
+    }
+
+    /// Same as `refresh_rng`, using `rand::thread_rng`.
+    #[cfg(feature = "std")]
+    pub fn refresh(&self, shares: &mut [Option<Share<POLY>>]) -> Result<(), &'static str> {
+        let mut rng = rand::thread_rng();
+        self.refresh_rng(shares, &mut rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{SecretSharing, Share};
+    use alloc::{vec, vec::Vec};
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    use rstest::rstest;
+
+    const POLY: u16 = 0x11d_u16;
+
+    fn make_shares(k: u8, secret: &[u8], n: usize) -> Vec<Option<Share<POLY>>> {
+        let mut rng = ChaCha8Rng::from_seed([0x40; 32]);
+        SecretSharing::<POLY>(k)
+            .dealer_rng(secret, &mut rng)
+            .take(n)
+            .map(Some)
+            .collect()
+    }
+
+    #[rstest]
+    #[case(1, 3)]
+    #[case(2, 2)]
+    #[case(3, 5)]
+    #[case(10, 30)]
+    fn refresh_keeps_secret(#[case] k: u8, #[case] n: usize) {
+        let sss = SecretSharing::<POLY>(k);
+        let secret = [11, 22, 33, 44];
+        let old = make_shares(k, &secret, n);
+        let mut new = old.clone();
+        let mut rng = ChaCha8Rng::from_seed([0x41; 32]);
+        for _ in 0..3 {
+            sss.refresh_rng(&mut new, &mut rng).unwrap();
+            assert_eq!(sss.recover(&new).unwrap(), secret);
+        }
+
+        if k > 1 {
+            assert!(old
+                .iter()
+                .zip(new.iter())
+                .all(|(o, n)| Vec::from(o.as_ref().unwrap()) != Vec::from(n.as_ref().unwrap())));
+        }
+    }
+
+    #[test]
+    fn refresh_skips_unknown_shares() {
+        let sss = SecretSharing::<POLY>(2);
+        let mut shares = make_shares(2, &[5, 6], 4);
+        shares[0] = None;
+        shares[2] = None;
+        let mut rng = ChaCha8Rng::from_seed([0x41; 32]);
+        sss.refresh_rng(&mut shares, &mut rng).unwrap();
+        assert!(shares[0].is_none() && shares[2].is_none());
+        assert_eq!(sss.recover(&shares).unwrap(), vec![5, 6]);
+    }
+
+    #[test]
+    fn invalid_messages_err() {
+        let sss = SecretSharing::<POLY>(2);
+        let mut rng = ChaCha8Rng::from_seed([0x41; 32]);
+        let messages = sss.refresh_messages_rng(1, 2, &[1, 2], &mut rng).unwrap();
+        let mut share = make_shares(2, &[5, 6], 1).remove(0).unwrap();
+        let original = Vec::from(&share);
+
+        // Addressed to another holder
+        assert!(sss.apply_refresh(1, &mut share, &[1], &messages).is_err());
+        // Duplicated sender
+        assert!(sss
+            .apply_refresh(1, &mut share, &[1], [&messages[0], &messages[0]])
+            .is_err());
+        // Wrong length
+        let other = sss.refresh_messages_rng(2, 3, &[1], &mut rng).unwrap();
+        assert!(sss.apply_refresh(1, &mut share, &[2], &other).is_err());
+        // Missing messages from some of the holders
+        let other = sss.refresh_messages_rng(2, 2, &[1, 2], &mut rng).unwrap();
+        assert!(sss
+            .apply_refresh(1, &mut share, &[1, 2], [&messages[0]])
+            .is_err());
+        assert!(sss.apply_refresh(1, &mut share, &[1, 2], []).is_err());
+        assert!(sss.apply_refresh(1, &mut share, &[], []).is_err());
+        // A message from an unexpected holder
+        assert!(sss
+            .apply_refresh(1, &mut share, &[1], [&messages[0], &other[0]])
+            .is_err());
+        assert_eq!(Vec::from(&share), original);
+
+        sss.apply_refresh(1, &mut share, &[1, 2], [&messages[0], &other[0]])
+            .unwrap();
+        assert_ne!(Vec::from(&share), original);
+
+        assert!(sss.refresh_messages_rng(1, 2, &[0, 1], &mut rng).is_err());
+    }
+}
@@ -0,0 +1,300 @@
+// Share repair, or enrollment of a new holder, without revealing the secret nor the helpers' shares
+// (Laing-Stinson). To compute the share at `x`, each of `k` helpers weighs its share with its
+// Lagrange coefficient at `x` and splits the result in `k` random additive parts, one per helper.
+// Every helper then sends the sum of the parts it received to the new holder, who adds them up.
+// Helpers only see random parts, and the new holder only sees random sums of its own share.
+
+use alloc::vec::Vec;
+use hashbrown::HashSet;
+
+#[cfg(feature = "zeroize_memory")]
+use zeroize::Zeroize;
+
+use super::field::GF256;
+use super::math;
+use super::share::{new_share, Share};
+use super::SecretSharing;
+
+/// A message of a share repair: either a masked part sent by the helper at `from` to the helper
+/// at `to`, or the sum of the parts received by the helper at `from`, sent to the new holder at `to`.
+#[derive(Clone)]
+#[cfg_attr(feature = "zeroize_memory", derive(Zeroize))]
+#[cfg_attr(feature = "zeroize_memory", zeroize(drop))]
+pub struct RepairMessage<const POLY: u16> {
+    /// The x coordinate of the sending helper.
+    pub from: u8,
+    /// The x coordinate of the receiving helper or new holder.
+    pub to: u8,
+    /// The masked contribution.
+    pub y: Vec<GF256<POLY>>,
+}
+
+/// Checks the messages sent to `to` and returns the sum of the first `k` of them.
+fn sum_messages<const POLY: u16>(
+    to: u8,
+    messages: &[&RepairMessage<POLY>],
+    k: usize,
+) -> Result<Vec<GF256<POLY>>, &'static str> {
+    if messages.is_empty() || messages.len() < k {
+        return Err("Not enough repair messages, one from each helper is required");
+    }
+    let mut senders = HashSet::new();
+    if !messages
+        .iter()
+        .all(|m| m.to == to && senders.insert(m.from))
+    {
+        return Err("Repair messages must be addressed to the receiver and from distinct helpers");
+    }
+    if messages.iter().any(|m| m.y.len() != messages[0].y.len()) {
+        return Err("All repair messages must have the same length");
+    }
+
+    Ok((0..messages[0].y.len())
+        .map(|i| messages[..k].iter().map(|m| m.y[i].clone()).sum())
+        .collect())
+}
+
+impl<const POLY: u16> SecretSharing<POLY> {
+    /// First round of a repair, run by the helper at `from` holding `share`: splits its weighted
+    /// share in random parts for the `k` `helpers` (including itself) to compute the share at `x`.
+    /// A random number generator has to be provided.
+    pub fn repair_messages_rng<R: rand::Rng>(
+        &self,
+        from: u8,
+        share: &Share<POLY>,
+        helpers: &[u8],
+        x: u8,
+        rng: &mut R,
+    ) -> Result<Vec<RepairMessage<POLY>>, &'static str> {
+        let mut distinct = HashSet::new();
+        if helpers.len() != self.0 as usize || !helpers.iter().all(|h| distinct.insert(*h)) {
+            return Err("Exactly k distinct helpers are required");
+        }
+        if x == 0 || helpers.contains(&0) || helpers.contains(&x) {
+            return Err("Helpers and repaired x coordinates must be distinct and non-zero");
+        }
+        let index = helpers
+            .iter()
+            .position(|h| *h == from)
+            .ok_or("The sending helper must be one of the helpers")?;
+
+        let x_samples: Vec<GF256<POLY>> = helpers.iter().map(|h| GF256(*h)).collect();
+        let weight = math::lagrange_coefficients(&x_samples, GF256(x)).swap_remove(index);
+
+        let mut random = alloc::vec![0u8; share.y.len()];
+        let mut remainder: Vec<GF256<POLY>> =
+            share.y.iter().map(|y| weight.clone() * y.clone()).collect();
+        let mut messages = Vec::with_capacity(helpers.len());
+        for &to in &helpers[1..] {
+            rng.fill(random.as_mut_slice());
+            let y: Vec<GF256<POLY>> = random.iter().map(|r| GF256(*r)).collect();
+            for (r, part) in remainder.iter_mut().zip(y.iter()) {
+                *r = r.clone() - part.clone();
+            }
+            messages.push(RepairMessage { from, to, y });
+        }
+        messages.insert(
+            0,
+            RepairMessage {
+                from,
+                to: helpers[0],
+                y: remainder,
+            },
+        );
+
+        #[cfg(feature = "zeroize_memory")]
+        random.zeroize();
+
+        Ok(messages)
+    }
+
+    /// Same as `repair_messages_rng`, using `rand::thread_rng`.
+    #[cfg(feature = "std")]
+    pub fn repair_messages(
+        &self,
+        from: u8,
+        share: &Share<POLY>,
+        helpers: &[u8],
+        x: u8,
+    ) -> Result<Vec<RepairMessage<POLY>>, &'static str> {
+        let mut rng = rand::thread_rng();
+        self.repair_messages_rng(from, share, helpers, x, &mut rng)
+    }
+
+    /// Second round of a repair, run by the helper at `from`: sums the parts received from the `k`
+    /// helpers into its contribution to the new holder at `x`.
+    pub fn repair_contribution<'a, T>(
+        &self,
+        from: u8,
+        x: u8,
+        messages: T,
+    ) -> Result<RepairMessage<POLY>, &'static str>
+    where
+        T: IntoIterator<Item = &'a RepairMessage<POLY>>,
+        T::IntoIter: Iterator<Item = &'a RepairMessage<POLY>>,
+    {
+        let messages: Vec<&RepairMessage<POLY>> = messages.into_iter().collect();
+        let y = sum_messages(from, &messages, self.0 as usize)?;
+        Ok(RepairMessage { from, to: x, y })
+    }
+
+    /// Last step of a repair, run by the new holder at `x`: sums the contributions of the `k`
+    /// helpers into its share. Without `share_x`, the share must be placed at position `x - 1`.
+    pub fn complete_repair<'a, T>(
+        &self,
+        x: u8,
+        contributions: T,
+    ) -> Result<Share<POLY>, &'static str>
+    where
+        T: IntoIterator<Item = &'a RepairMessage<POLY>>,
+        T::IntoIter: Iterator<Item = &'a RepairMessage<POLY>>,
+    {
+        let contributions: Vec<&RepairMessage<POLY>> = contributions.into_iter().collect();
+        let y = sum_messages(x, &contributions, self.0 as usize)?;
+        Ok(new_share(GF256(x), y))
+    }
+
+    /// Runs a whole repair in-process, as each participant would in the protocol: the first `k`
+    /// known shares help compute the share at `x`. Shares are passed as in `recover`.
+    ///
+    /// Example:
+    /// ```
+    /// # use ssskit::{ SecretSharing, Share };
+    /// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    /// # const POLY: u16 = 0x11d_u16;
+    /// # let sss = SecretSharing::<POLY>(3);
+    /// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// let mut shares = sss
+    ///     .dealer_rng(&[1, 2, 3], &mut rng)
+    ///     .take(5)
+    ///     .map(Some)
+    ///     .collect::<Vec<Option<Share<POLY>>>>();
+    /// // The holder of the second share lost it
+    /// let lost = shares[1].take().unwrap();
+    /// let repaired = sss.repair_share_rng(&shares, 2, &mut rng).unwrap();
+    /// assert_eq!(Vec::from(&repaired), Vec::from(&lost));
+    /// ```
+    pub fn repair_share_rng<R: rand::Rng>(
+        &self,
+        shares: &[Option<Share<POLY>>],
+        x: u8,
+        rng: &mut R,
+    ) -> Result<Share<POLY>, &'static str> {
+        let (positions, values) = self.collect_positioned(shares)?;
+        let helpers: Vec<u8> = values.iter().take(self.0 as usize).map(|s| s.x.0).collect();
+
+        let mut messages = Vec::with_capacity(helpers.len() * helpers.len());
+        for (&i, &from) in positions.iter().zip(helpers.iter()) {
+            let share = shares[i].as_ref().unwrap();
+            messages.extend(self.repair_messages_rng(from, share, &helpers, x, rng)?);
+        }
+        let contributions = helpers
+            .iter()
+            .map(|&h| self.repair_contribution(h, x, messages.iter().filter(|m| m.to == h)))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.complete_repair(x, &contributions)
+    }
+
+    /// Same as `repair_share_rng`, using `rand::thread_rng`.
+    #[cfg(feature = "std")]
+    pub fn repair_share(
+        &self,
+        shares: &[Option<Share<POLY>>],
+        x: u8,
+    ) -> Result<Share<POLY>, &'static str> {
+        let mut rng = rand::thread_rng();
+        self.repair_share_rng(shares, x, &mut rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{SecretSharing, Share};
+    use alloc::{vec, vec::Vec};
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    use rstest::rstest;
+
+    const POLY: u16 = 0x11d_u16;
+
+    fn make_shares(k: u8, n: usize) -> Vec<Option<Share<POLY>>> {
+        let mut rng = ChaCha8Rng::from_seed([0x42; 32]);
+        SecretSharing::<POLY>(k)
+            .dealer_rng(&[2, 7, 1, 8], &mut rng)
+            .take(n)
+            .map(Some)
+            .collect()
+    }
+
+    #[rstest]
+    #[case(1, 3, 2)]
+    #[case(2, 3, 1)]
+    #[case(3, 5, 5)]
+    #[case(10, 20, 13)]
+    fn repairs_lost_share(#[case] k: u8, #[case] n: usize, #[case] x: u8) {
+        let sss = SecretSharing::<POLY>(k);
+        let mut shares = make_shares(k, n);
+        let lost = shares[x as usize - 1].take().unwrap();
+
+        let mut rng = ChaCha8Rng::from_seed([0x43; 32]);
+        let repaired = sss.repair_share_rng(&shares, x, &mut rng).unwrap();
+        assert_eq!(Vec::from(&repaired), Vec::from(&lost));
+    }
+
+    #[test]
+    fn enrolls_new_holder() {
+        let sss = SecretSharing::<POLY>(3);
+        let mut shares = make_shares(3, 3);
+        let mut rng = ChaCha8Rng::from_seed([0x43; 32]);
+        let enrolled = sss.repair_share_rng(&shares, 200, &mut rng).unwrap();
+
+        shares.resize(200, None);
+        shares[0] = None;
+        shares[199] = Some(enrolled);
+        assert_eq!(sss.recover(&shares).unwrap(), vec![2, 7, 1, 8]);
+    }
+
+    #[test]
+    fn helpers_only_see_masked_parts() {
+        let sss = SecretSharing::<POLY>(2);
+        let shares = make_shares(2, 2);
+        let share = shares[0].as_ref().unwrap();
+        let mut rng = ChaCha8Rng::from_seed([0x43; 32]);
+        let first = sss
+            .repair_messages_rng(1, share, &[1, 2], 3, &mut rng)
+            .unwrap();
+        let second = sss
+            .repair_messages_rng(1, share, &[1, 2], 3, &mut rng)
+            .unwrap();
+        // The part sent to the other helper is fresh randomness every time
+        assert_eq!(first[1].to, 2);
+        assert_ne!(first[1].y, second[1].y);
+    }
+
+    #[test]
+    fn invalid_parameters_err() {
+        let sss = SecretSharing::<POLY>(2);
+        let shares = make_shares(2, 3);
+        let share = shares[0].as_ref().unwrap();
+        let mut rng = ChaCha8Rng::from_seed([0x43; 32]);
+
+        // Repairing the secret itself
+        assert!(sss.repair_share_rng(&shares, 0, &mut rng).is_err());
+        // Repairing a helper's share
+        assert!(sss.repair_share_rng(&shares, 1, &mut rng).is_err());
+        // Wrong number of helpers
+        assert!(sss
+            .repair_messages_rng(1, share, &[1, 2, 3], 4, &mut rng)
+            .is_err());
+        // Sender is not a helper
+        assert!(sss
+            .repair_messages_rng(3, share, &[1, 2], 4, &mut rng)
+            .is_err());
+
+        let messages = sss
+            .repair_messages_rng(1, share, &[1, 2], 4, &mut rng)
+            .unwrap();
+        assert!(sss.repair_contribution(1, 4, &messages[..1]).is_err());
+        assert!(sss.repair_contribution(1, 4, &messages).is_err());
+        assert!(sss.complete_repair(4, &messages).is_err());
+    }
+}
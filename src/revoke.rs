@@ -0,0 +1,253 @@
+// Share revocation: the holders left after revoking some shares run a refresh among themselves
+// only, so the revoked shares receive no update and no longer lie on the sharing polynomial.
+// Shares are tagged with the epoch of the last refresh, so that shares of different epochs are
+// rejected instead of silently recovering a wrong secret.
+
+use alloc::vec::Vec;
+
+use super::share::{share_x, Share};
+use super::SecretSharing;
+
+/// A share tagged with the epoch of the sharing it belongs to, incremented at every revocation.
+///
+/// Can be serialized to and from a byte array as `[epoch (u32 big endian), share bytes...]`.
+#[derive(Clone)]
+pub struct EpochShare<const POLY: u16> {
+    /// The epoch of the share.
+    pub epoch: u32,
+    /// The share.
+    pub share: Share<POLY>,
+}
+
+impl<const POLY: u16> From<&EpochShare<POLY>> for Vec<u8> {
+    fn from(s: &EpochShare<POLY>) -> Vec<u8> {
+        let mut bytes = Vec::from(&s.share);
+        bytes.splice(0..0, s.epoch.to_be_bytes());
+        bytes
+    }
+}
+
+impl<const POLY: u16> core::convert::TryFrom<&[u8]> for EpochShare<POLY> {
+    type Error = &'static str;
+
+    fn try_from(s: &[u8]) -> Result<EpochShare<POLY>, Self::Error> {
+        if s.len() < 4 {
+            return Err("An EpochShare must be at least 4 bytes long");
+        }
+        Ok(EpochShare {
+            epoch: u32::from_be_bytes([s[0], s[1], s[2], s[3]]),
+            share: Share::try_from(&s[4..])?,
+        })
+    }
+}
+
+/// Returns the epoch of the known shares, or an `Err` if they are from different epochs.
+fn common_epoch<const POLY: u16>(
+    shares: &[&Option<EpochShare<POLY>>],
+) -> Result<u32, &'static str> {
+    let mut epochs = shares.iter().filter_map(|s| s.as_ref().map(|s| s.epoch));
+    let epoch = epochs
+        .next()
+        .ok_or("Not enough shares to recover original secret")?;
+    if epochs.any(|e| e != epoch) {
+        return Err("All shares must be from the same epoch");
+    }
+    Ok(epoch)
+}
+
+impl<const POLY: u16> SecretSharing<POLY> {
+    /// Revokes the shares at the `revoked` x coordinates: they are removed from `shares`, and the
+    /// remaining holders refresh their shares among themselves (see `refresh_rng`) into the next
+    /// epoch, which is returned. Shares are passed as in `recover`.
+    /// Every revoked x coordinate must be one of the known shares, and at least `k` shares must
+    /// remain, otherwise an `Err` is returned and `shares` is unchanged.
+    ///
+    /// Example:
+    /// ```
+    /// # use ssskit::{ EpochShare, SecretSharing };
+    /// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    /// # const POLY: u16 = 0x11d_u16;
+    /// # let sss = SecretSharing::<POLY>(2);
+    /// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// let mut shares = sss
+    ///     .dealer_rng(&[1, 2, 3], &mut rng)
+    ///     .take(4)
+    ///     .map(|share| Some(EpochShare { epoch: 0, share }))
+    ///     .collect::<Vec<Option<EpochShare<POLY>>>>();
+    /// // The laptop holding the third share was stolen
+    /// let stolen = shares[2].clone();
+    /// assert_eq!(sss.revoke_rng(&mut shares, &[3], &mut rng).unwrap(), 1);
+    /// assert_eq!(sss.recover_epoch(&shares).unwrap(), vec![1, 2, 3]);
+    /// // The stolen share cannot be combined with current ones
+    /// shares[0] = None;
+    /// shares[2] = stolen;
+    /// assert!(sss.recover_epoch(&shares).is_err());
+    /// ```
+    pub fn revoke_rng<R: rand::Rng>(
+        &self,
+        shares: &mut [Option<EpochShare<POLY>>],
+        revoked: &[u8],
+        rng: &mut R,
+    ) -> Result<u32, &'static str> {
+        let epoch = common_epoch(&shares.iter().collect::<Vec<_>>())?;
+        let next = epoch
+            .checked_add(1)
+            .ok_or("No epoch left to refresh into")?;
+
+        let current: Vec<u8> = shares
+            .iter()
+            .enumerate()
+            .filter_map(|(i, s)| s.as_ref().map(|s| share_x(i, &s.share)))
+            .collect();
+        if revoked.iter().any(|x| !current.contains(x)) {
+            return Err("Revoked x coordinates must be among the current shares");
+        }
+
+        let mut remaining: Vec<Option<Share<POLY>>> = shares
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                s.as_ref()
+                    .filter(|s| !revoked.contains(&share_x(i, &s.share)))
+                    .map(|s| s.share.clone())
+            })
+            .collect();
+        self.refresh_rng(&mut remaining, rng)?;
+
+        for (share, refreshed) in shares.iter_mut().zip(remaining) {
+            *share = refreshed.map(|share| EpochShare { epoch: next, share });
+        }
+        Ok(next)
+    }
+
+    /// Same as `revoke_rng`, using `rand::thread_rng`.
+    #[cfg(feature = "std")]
+    pub fn revoke(
+        &self,
+        shares: &mut [Option<EpochShare<POLY>>],
+        revoked: &[u8],
+    ) -> Result<u32, &'static str> {
+        let mut rng = rand::thread_rng();
+        self.revoke_rng(shares, revoked, &mut rng)
+    }
+
+    /// Same as `recover`, but for epoch-tagged shares, which must all be from the same epoch.
+    pub fn recover_epoch<'a, T>(&self, shares: T) -> Result<Vec<u8>, &'static str>
+    where
+        T: IntoIterator<Item = &'a Option<EpochShare<POLY>>>,
+        T::IntoIter: Iterator<Item = &'a Option<EpochShare<POLY>>>,
+    {
+        let shares: Vec<&Option<EpochShare<POLY>>> = shares.into_iter().collect();
+        common_epoch(&shares)?;
+        let shares: Vec<Option<Share<POLY>>> = shares
+            .iter()
+            .map(|s| s.as_ref().map(|s| s.share.clone()))
+            .collect();
+        self.recover(&shares)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EpochShare;
+    use crate::SecretSharing;
+    use alloc::{vec, vec::Vec};
+    use core::convert::TryFrom;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    use rstest::rstest;
+
+    const POLY: u16 = 0x11d_u16;
+
+    fn make_shares(k: u8, n: usize) -> Vec<Option<EpochShare<POLY>>> {
+        let mut rng = ChaCha8Rng::from_seed([0x43; 32]);
+        SecretSharing::<POLY>(k)
+            .dealer_rng(&[6, 6, 2], &mut rng)
+            .take(n)
+            .map(|share| Some(EpochShare { epoch: 0, share }))
+            .collect()
+    }
+
+    #[rstest]
+    #[case(2, 4, vec![3])]
+    #[case(3, 5, vec![1, 5])]
+    #[case(3, 3, vec![])]
+    #[case(5, 10, vec![2, 4, 6, 8, 10])]
+    fn revoked_shares_are_invalidated(#[case] k: u8, #[case] n: usize, #[case] revoked: Vec<u8>) {
+        let sss = SecretSharing::<POLY>(k);
+        let mut shares = make_shares(k, n);
+        let old = shares.clone();
+        let mut rng = ChaCha8Rng::from_seed([0x44; 32]);
+
+        assert_eq!(sss.revoke_rng(&mut shares, &revoked, &mut rng).unwrap(), 1);
+        assert_eq!(sss.recover_epoch(&shares).unwrap(), vec![6, 6, 2]);
+        for &x in &revoked {
+            assert!(shares[x as usize - 1].is_none());
+        }
+
+        for &x in &revoked {
+            // Mixed epochs are rejected
+            let mut mixed = shares.clone();
+            mixed[x as usize - 1] = old[x as usize - 1].clone();
+            assert!(sss.recover_epoch(&mixed).is_err());
+
+            // A forged epoch tag does not help either
+            let mut forged = old[x as usize - 1].clone().unwrap();
+            forged.epoch = 1;
+            let mut known = 1;
+            for (i, share) in mixed.iter_mut().enumerate() {
+                if i == x as usize - 1 {
+                    *share = Some(forged.clone());
+                } else if share.is_some() && known < k {
+                    known += 1;
+                } else {
+                    *share = None;
+                }
+            }
+            if k > 1 {
+                assert_ne!(sss.recover_epoch(&mixed).unwrap(), vec![6, 6, 2]);
+            }
+        }
+
+        assert_eq!(sss.revoke_rng(&mut shares, &[], &mut rng).unwrap(), 2);
+        assert_eq!(sss.recover_epoch(&shares).unwrap(), vec![6, 6, 2]);
+    }
+
+    #[test]
+    fn too_many_revocations_err() {
+        let sss = SecretSharing::<POLY>(3);
+        let mut shares = make_shares(3, 4);
+        let mut rng = ChaCha8Rng::from_seed([0x44; 32]);
+        assert!(sss.revoke_rng(&mut shares, &[1, 2], &mut rng).is_err());
+        assert!(shares.iter().all(|s| s.as_ref().unwrap().epoch == 0));
+    }
+
+    #[test]
+    fn unknown_revocations_err() {
+        let sss = SecretSharing::<POLY>(2);
+        let mut shares = make_shares(2, 4);
+        shares[1] = None;
+        let mut rng = ChaCha8Rng::from_seed([0x44; 32]);
+        // A typo, and a share that is not known
+        assert!(sss.revoke_rng(&mut shares, &[3, 7], &mut rng).is_err());
+        assert!(sss.revoke_rng(&mut shares, &[2], &mut rng).is_err());
+        assert!(sss.revoke_rng(&mut shares, &[0], &mut rng).is_err());
+        assert!(shares.iter().flatten().all(|s| s.epoch == 0));
+        assert_eq!(sss.revoke_rng(&mut shares, &[3], &mut rng).unwrap(), 1);
+    }
+
+    #[test]
+    fn serialization_roundtrip() {
+        let sss = SecretSharing::<POLY>(2);
+        let shares: Vec<Option<EpochShare<POLY>>> = make_shares(2, 2)
+            .iter()
+            .map(|s| {
+                let mut s = s.clone().unwrap();
+                s.epoch = 0x01020304;
+                Some(EpochShare::try_from(Vec::from(&s).as_slice()).unwrap())
+            })
+            .collect();
+        assert_eq!(shares[0].as_ref().unwrap().epoch, 0x01020304);
+        assert_eq!(sss.recover_epoch(&shares).unwrap(), vec![6, 6, 2]);
+        assert!(EpochShare::<POLY>::try_from(&[0u8; 3][..]).is_err());
+    }
+}
@@ -0,0 +1,240 @@
+// Robust recovery of secrets from shares that may have been corrupted.
+// Shamir shares are Reed-Solomon codewords, so given `n` shares of a threshold `k` sharing,
+// up to `(n - k) / 2` corrupted shares can be detected and corrected.
+
+use alloc::vec::Vec;
+use hashbrown::HashSet;
+
+use super::field::GF256;
+use super::math;
+use super::share::{Share, ShareWithX};
+use super::SecretSharing;
+
+impl<const POLY: u16> SecretSharing<POLY> {
+    /// Given an iterable collection of shares, recovers the original secret correcting up to
+    /// `(n - k) / 2` corrupted shares, where `n` is the number of shares given and `k` the threshold.
+    /// Returns the secret along with the positions of the corrupted shares, or an `Err` if there
+    /// are not enough shares or too many of them are corrupted.
+    ///
+    /// Example:
+    /// ```
+    /// # use ssskit::{ SecretSharing, Share };
+    /// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    /// # const POLY: u16 = 0x11d_u16;
+    /// # let sss = SecretSharing::<POLY>(3);
+    /// # let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// let mut shares = sss
+    ///     .dealer_rng::<ChaCha8Rng>(&[1, 2, 3], &mut rng)
+    ///     .take(5)
+    ///     .map(Some)
+    ///     .collect::<Vec<Option<Share<POLY>>>>();
+    /// // Corrupt a share
+    /// shares[1].as_mut().unwrap().y[0].0 ^= 0xff;
+    /// let (secret, corrupted) = sss.recover_robust(&shares).unwrap();
+    /// assert_eq!(secret, vec![1, 2, 3]);
+    /// assert_eq!(corrupted, vec![1]);
+    /// ```
+    pub fn recover_robust<'a, T>(&self, shares: T) -> Result<(Vec<u8>, Vec<usize>), &'static str>
+    where
+        T: IntoIterator<Item = &'a Option<Share<POLY>>>,
+        T::IntoIter: Iterator<Item = &'a Option<Share<POLY>>>,
+    {
+        let (positions, values) = self.collect_positioned(shares)?;
+        let (polys, errors) = decode(&values, self.0)?;
+
+        let secret = polys.iter().map(|p| p[0].0).collect();
+        Ok((secret, errors.into_iter().map(|i| positions[i]).collect()))
+    }
+
+    /// Given an iterable collection of shares, checks whether they all lie on a single polynomial of
+    /// degree `k - 1`, that is whether any `k` of them recover the same secret.
+    /// Returns the positions of the minimal set of inconsistent shares, which is empty if the pool
+    /// is consistent. At least `k + 1` shares are required to detect an inconsistency, and
+    /// `k + 2 * c` to identify `c` inconsistent shares, otherwise an `Err` is returned. Any `k`
+    /// shares lie on some polynomial of degree `k - 1`, so fewer than `k + 1` shares cannot be
+    /// checked at all and are an `Err` as well.
+    ///
+    /// Example:
+    /// ```
+    /// # use ssskit::{ SecretSharing, Share };
+    /// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    /// # const POLY: u16 = 0x11d_u16;
+    /// # let sss = SecretSharing::<POLY>(2);
+    /// # let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// let mut shares = sss
+    ///     .dealer_rng::<ChaCha8Rng>(&[1, 2, 3], &mut rng)
+    ///     .take(4)
+    ///     .map(Some)
+    ///     .collect::<Vec<Option<Share<POLY>>>>();
+    /// assert!(sss.check_consistency(&shares).unwrap().is_empty());
+    /// // A custodian hands in a forged share
+    /// shares[2].as_mut().unwrap().y[1].0 ^= 0x01;
+    /// assert_eq!(sss.check_consistency(&shares).unwrap(), vec![2]);
+    /// ```
+    pub fn check_consistency<'a, T>(&self, shares: T) -> Result<Vec<usize>, &'static str>
+    where
+        T: IntoIterator<Item = &'a Option<Share<POLY>>>,
+        T::IntoIter: Iterator<Item = &'a Option<Share<POLY>>>,
+    {
+        let (positions, values) = self.collect_positioned(shares)?;
+        if values.len() <= self.0 as usize {
+            return Err("At least k + 1 shares are required to check their consistency");
+        }
+        let (_, errors) =
+            decode(&values, self.0).map_err(|_| "Too many inconsistent shares to identify them")?;
+        Ok(errors.into_iter().map(|i| positions[i]).collect())
+    }
+
+    /// Same as `collect_shares`, but also returns the position of each known share in `shares`
+    /// and rejects shares with duplicated `x` coordinates.
+    pub(crate) fn collect_positioned<'a, T>(
+        &self,
+        shares: T,
+    ) -> Result<(Vec<usize>, Vec<ShareWithX<POLY>>), &'static str>
+    where
+        T: IntoIterator<Item = &'a Option<Share<POLY>>>,
+        T::IntoIter: Iterator<Item = &'a Option<Share<POLY>>>,
+    {
+        let shares: Vec<&Option<Share<POLY>>> = shares.into_iter().collect();
+        let positions = (0..shares.len()).filter(|&i| shares[i].is_some()).collect();
+        let values = self.collect_shares(shares)?;
+
+        let mut xs = HashSet::new();
+        if !values.iter().all(|s| xs.insert(s.x.0)) {
+            return Err("Shares must have distinct x coordinates");
+        }
+
+        Ok((positions, values))
+    }
+}
+
+/// Decodes every byte chunk of the shares as a Reed-Solomon codeword of a polynomial of degree lower
+/// than `k`. Returns the polynomials along with the indices of the shares that are off any of them.
+pub(crate) fn decode<const POLY: u16>(
+    values: &[ShareWithX<POLY>],
+    k: u8,
+) -> Result<(Vec<Vec<GF256<POLY>>>, Vec<usize>), &'static str> {
+    let k = (k as usize).max(1);
+    let x_samples: Vec<GF256<POLY>> = values.iter().map(|s| s.x.clone()).collect();
+
+    let mut errors: Vec<usize> = Vec::new();
+    let mut polys = Vec::with_capacity(values[0].y.len());
+    for i in 0..values[0].y.len() {
+        let y_samples: Vec<GF256<POLY>> = values.iter().map(|s| s.y[i].clone()).collect();
+        let (poly, chunk_errors) = math::berlekamp_welch(&x_samples, &y_samples, k)
+            .ok_or("Too many corrupted shares to recover original secret")?;
+        errors.extend(chunk_errors);
+        polys.push(poly);
+    }
+
+    errors.sort_unstable();
+    errors.dedup();
+    Ok((polys, errors))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{SecretSharing, Share};
+    use alloc::{vec, vec::Vec};
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    use rstest::rstest;
+
+    const POLY: u16 = 0x11d_u16;
+
+    fn make_shares(k: u8, secret: &[u8], n: usize) -> Vec<Option<Share<POLY>>> {
+        let sss = SecretSharing::<POLY>(k);
+        let mut rng = ChaCha8Rng::from_seed([0x33; 32]);
+        sss.dealer_rng(secret, &mut rng).take(n).map(Some).collect()
+    }
+
+    #[rstest]
+    #[case(3, 5, vec![4])]
+    #[case(3, 9, vec![0, 5, 8])]
+    #[case(5, 15, vec![1, 2, 3, 10, 14])]
+    #[case(1, 5, vec![0, 3])]
+    fn corrects_corrupted_shares(#[case] k: u8, #[case] n: usize, #[case] bad: Vec<usize>) {
+        let sss = SecretSharing::<POLY>(k);
+        let secret = [10, 20, 30, 40];
+        let mut shares = make_shares(k, &secret, n);
+        for (j, &i) in bad.iter().enumerate() {
+            let share = shares[i].as_mut().unwrap();
+            share.y[j % secret.len()].0 ^= 0x5a;
+        }
+
+        let (recovered, corrupted) = sss.recover_robust(&shares).unwrap();
+        assert_eq!(recovered, secret);
+        assert_eq!(corrupted, bad);
+    }
+
+    #[test]
+    fn positions_skip_unknown_shares() {
+        let sss = SecretSharing::<POLY>(2);
+        let mut shares = make_shares(2, &[1, 2], 5);
+        shares[0] = None;
+        shares[3].as_mut().unwrap().y[1].0 ^= 1;
+        let (secret, corrupted) = sss.recover_robust(&shares).unwrap();
+        assert_eq!(secret, vec![1, 2]);
+        assert_eq!(corrupted, vec![3]);
+    }
+
+    #[test]
+    fn no_corruption_works() {
+        let sss = SecretSharing::<POLY>(4);
+        let shares = make_shares(4, &[5, 6, 7], 4);
+        let (secret, corrupted) = sss.recover_robust(&shares).unwrap();
+        assert_eq!(secret, vec![5, 6, 7]);
+        assert!(corrupted.is_empty());
+    }
+
+    #[test]
+    fn too_many_corrupted_shares_err() {
+        let sss = SecretSharing::<POLY>(3);
+        let mut shares = make_shares(3, &[5, 6, 7], 5);
+        shares[0].as_mut().unwrap().y[0].0 ^= 1;
+        shares[1].as_mut().unwrap().y[0].0 ^= 2;
+        assert!(sss.recover_robust(&shares).is_err());
+
+        let mut shares = make_shares(3, &[5, 6, 7], 4);
+        shares[0].as_mut().unwrap().y[0].0 ^= 1;
+        assert!(sss.recover_robust(&shares).is_err());
+    }
+
+    #[rstest]
+    #[case(3, 4, vec![])]
+    #[case(3, 6, vec![5])]
+    #[case(4, 12, vec![0, 6, 7, 11])]
+    fn check_consistency_identifies_cheaters(
+        #[case] k: u8,
+        #[case] n: usize,
+        #[case] cheaters: Vec<usize>,
+    ) {
+        let sss = SecretSharing::<POLY>(k);
+        let mut shares = make_shares(k, &[1, 2, 3], n);
+        for &i in &cheaters {
+            let share = shares[i].as_mut().unwrap();
+            share.y[2].0 = share.y[2].0.wrapping_add(1);
+        }
+        assert_eq!(sss.check_consistency(&shares).unwrap(), cheaters);
+    }
+
+    #[test]
+    fn check_consistency_detects_unidentifiable_cheaters() {
+        let sss = SecretSharing::<POLY>(3);
+        let mut shares = make_shares(3, &[1, 2, 3], 4);
+        shares[3].as_mut().unwrap().y[0].0 ^= 1;
+        assert!(sss.check_consistency(&shares).is_err());
+
+        let shares = make_shares(3, &[1, 2, 3], 2);
+        assert!(sss.check_consistency(&shares).is_err());
+        // Exactly k shares are always on some polynomial, so nothing can be checked
+        let shares = make_shares(3, &[1, 2, 3], 3);
+        assert!(sss.check_consistency(&shares).is_err());
+    }
+
+    #[test]
+    fn insufficient_shares_err() {
+        let sss = SecretSharing::<POLY>(3);
+        let shares = make_shares(3, &[5, 6, 7], 2);
+        assert!(sss.recover_robust(&shares).is_err());
+    }
+}
@@ -0,0 +1,98 @@
+// Shamir's secret sharing over the scalar field of curve25519, for the threshold protocols working
+// in the exponent of a group of prime order. Mirrors `math.rs`, where `GF256` plays the role of
+// `Scalar`: polynomial coefficients go from the highest degree down and end with the secret.
+
+use alloc::vec::Vec;
+use curve25519_dalek::scalar::Scalar;
+
+/// Returns a uniformly random scalar.
+pub(crate) fn random_scalar<R: rand::Rng>(rng: &mut R) -> Scalar {
+    let mut bytes = [0u8; 64];
+    rng.fill(&mut bytes[..]);
+    let scalar = Scalar::from_bytes_mod_order_wide(&bytes);
+
+    #[cfg(feature = "zeroize_memory")]
+    zeroize::Zeroize::zeroize(&mut bytes);
+
+    scalar
+}
+
+/// Generates `k` polynomial coefficients, being the last one `s` and the others random.
+/// Coefficient degrees go from higher to lower in the returned vector order.
+pub(crate) fn random_polynomial<R: rand::Rng>(s: Scalar, k: u8, rng: &mut R) -> Vec<Scalar> {
+    let mut poly: Vec<Scalar> = (1..k).map(|_| random_scalar(rng)).collect();
+    poly.push(s);
+    poly
+}
+
+/// Evaluates the polynomial, with coefficients from the highest degree down, at `x`.
+pub(crate) fn evaluate(poly: &[Scalar], x: Scalar) -> Scalar {
+    poly.iter().fold(Scalar::ZERO, |acc, c| acc * x + c)
+}
+
+/// Returns the shares at `x = 1..=n` of a random polynomial of degree `k - 1` with `s` as constant
+/// term.
+pub(crate) fn deal<R: rand::Rng>(s: Scalar, k: u8, n: u8, rng: &mut R) -> Vec<Scalar> {
+    let poly = random_polynomial(s, k, rng);
+    let shares = (1..=n).map(|x| evaluate(&poly, Scalar::from(x))).collect();
+
+    #[cfg(feature = "zeroize_memory")]
+    {
+        let mut poly = poly;
+        zeroize::Zeroize::zeroize(&mut poly);
+    }
+
+    shares
+}
+
+/// Returns the Lagrange basis coefficients of the sample points at `x`, such that the value at `x`
+/// of the polynomial going through the samples is the sum of each `y` sample times its coefficient.
+/// The sample points must be distinct.
+pub(crate) fn lagrange_coefficients(x_samples: &[Scalar], x: Scalar) -> Vec<Scalar> {
+    x_samples
+        .iter()
+        .map(|x_i| {
+            let (numerator, denominator) = x_samples
+                .iter()
+                .filter(|x_j| *x_j != x_i)
+                .fold((Scalar::ONE, Scalar::ONE), |(n, d), x_j| {
+                    (n * (x - x_j), d * (x_i - x_j))
+                });
+            numerator * denominator.invert()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{deal, lagrange_coefficients};
+    use alloc::vec::Vec;
+    use curve25519_dalek::scalar::Scalar;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(1, 1)]
+    #[case(2, 3)]
+    #[case(5, 9)]
+    fn interpolates_in_the_exponent_field(#[case] k: u8, #[case] n: u8) {
+        let mut rng = ChaCha8Rng::from_seed([0x48; 32]);
+        let secret = Scalar::from(0xdead_beef_u64);
+        let shares = deal(secret, k, n, &mut rng);
+        assert_eq!(shares.len(), n as usize);
+
+        let xs: Vec<Scalar> = (n - k + 1..=n).map(Scalar::from).collect();
+        let weights = lagrange_coefficients(&xs, Scalar::ZERO);
+        let recovered: Scalar = weights
+            .iter()
+            .zip(shares[(n - k) as usize..].iter())
+            .map(|(w, y)| w * y)
+            .sum();
+        assert_eq!(recovered, secret);
+
+        // At a sample point, the weights are the indicator of that point
+        let weights = lagrange_coefficients(&xs, xs[0]);
+        assert_eq!(weights[0], Scalar::ONE);
+        assert!(weights[1..].iter().all(|w| *w == Scalar::ZERO));
+    }
+}
@@ -115,6 +115,34 @@ pub type Share<const POLY: u16> = ShareNoX<POLY>;
 #[cfg(feature = "share_x")]
 pub type Share<const POLY: u16> = ShareWithX<POLY>;
 
+/// Builds a `Share` at `x` from its y coordinates. Without `share_x` the `x` coordinate is dropped,
+/// so the share must be placed at position `x - 1`.
+#[allow(unused_variables)]
+pub(crate) fn new_share<const POLY: u16>(x: GF256<POLY>, y: Vec<GF256<POLY>>) -> Share<POLY> {
+    #[cfg(feature = "share_x")]
+    {
+        Share { x, y }
+    }
+    #[cfg(not(feature = "share_x"))]
+    {
+        Share { y }
+    }
+}
+
+/// Returns the `x` coordinate of a share at `position` in a list of shares, which is the 1-based
+/// position without `share_x`.
+#[allow(unused_variables)]
+pub(crate) fn share_x<const POLY: u16>(position: usize, share: &Share<POLY>) -> u8 {
+    #[cfg(feature = "share_x")]
+    {
+        share.x.0
+    }
+    #[cfg(not(feature = "share_x"))]
+    {
+        position as u8 + 1
+    }
+}
+
 /// Converts a ShareNoX to a vector of bytes, where the bytes are the y values.
 impl<const POLY: u16> From<&ShareNoX<POLY>> for Vec<u8> {
     fn from(s: &ShareNoX<POLY>) -> Vec<u8> {
@@ -0,0 +1,459 @@
+//! Streaming secret sharing over `std::io`, for secrets too large to be held in memory.
+//!
+//! The secret is read in chunks of [`STREAM_CHUNK_LENGTH`] bytes, which are shared with one
+//! polynomial per byte exactly as `dealer_rng` does, and each share is written to its own stream.
+//! Every share stream starts with a [`StreamHeader`], followed by the share `y` bytes.
+//! Recovery reads the share streams in lock-step chunks and writes the secret to a single stream.
+
+use hashbrown::HashSet;
+use std::io::{self, Read, Write};
+
+use super::field::GF256;
+use super::math;
+use super::SecretSharing;
+
+/// Length in bytes of the secret chunks read at a time.
+pub const STREAM_CHUNK_LENGTH: usize = 64 * 1024;
+
+const MAGIC: &[u8; 4] = b"SSSK";
+const VERSION: u8 = 1;
+
+/// Header prefixed to every share stream.
+///
+/// Serialized as `[b"SSSK", version, threshold, x, poly (2 bytes, big endian)]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StreamHeader {
+    /// Minimum number of shares required to recover the secret.
+    pub threshold: u8,
+    /// The x coordinate of the share.
+    pub x: u8,
+    /// The irreducible polynomial of the field the secret was shared over.
+    pub poly: u16,
+}
+
+impl StreamHeader {
+    /// Length in bytes of a serialized header.
+    pub const LENGTH: usize = 9;
+
+    /// Reads and validates a header from the beginning of a share stream.
+    pub fn read_from<R: Read>(reader: &mut R) -> io::Result<StreamHeader> {
+        let mut bytes = [0u8; Self::LENGTH];
+        reader.read_exact(&mut bytes).map_err(|e| match e.kind() {
+            io::ErrorKind::UnexpectedEof => invalid_data("Share stream is too short for a header"),
+            _ => e,
+        })?;
+
+        if &bytes[..4] != MAGIC {
+            return Err(invalid_data("Share stream does not start with a header"));
+        }
+        if bytes[4] != VERSION {
+            return Err(invalid_data("Unsupported share stream version"));
+        }
+
+        Ok(StreamHeader {
+            threshold: bytes[5],
+            x: bytes[6],
+            poly: u16::from_be_bytes([bytes[7], bytes[8]]),
+        })
+    }
+
+    /// Writes the header to the beginning of a share stream.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let mut bytes = [0u8; Self::LENGTH];
+        bytes[..4].copy_from_slice(MAGIC);
+        bytes[4] = VERSION;
+        bytes[5] = self.threshold;
+        bytes[6] = self.x;
+        bytes[7..].copy_from_slice(&self.poly.to_be_bytes());
+        writer.write_all(&bytes)
+    }
+}
+
+pub(crate) fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Reads from `reader` until `buf` is full or the end of the stream is reached.
+/// Returns the number of bytes read.
+pub(crate) fn fill<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+impl<const POLY: u16> SecretSharing<POLY> {
+    /// Reads a secret from `secret` in chunks and writes one share to each of the `shares` writers,
+    /// the `i`th writer receiving the share with `x = i + 1`.
+    /// Memory use is bounded by the chunk length, regardless of the length of the secret.
+    /// Returns the number of secret bytes read.
+    ///
+    /// Example:
+    /// ```
+    /// # use ssskit::SecretSharing;
+    /// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    /// # const POLY: u16 = 0x11d_u16;
+    /// let sss = SecretSharing::<POLY>(2);
+    /// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// let secret = vec![7u8; 100_000];
+    /// let mut shares = vec![Vec::new(); 3];
+    /// let read = sss.split_stream(secret.as_slice(), &mut shares, &mut rng).unwrap();
+    /// assert_eq!(read, 100_000);
+    /// ```
+    pub fn split_stream<R: Read, W: Write, G: rand::Rng>(
+        &self,
+        mut secret: R,
+        shares: &mut [W],
+        rng: &mut G,
+    ) -> io::Result<u64> {
+        if shares.is_empty() || shares.len() > u8::MAX as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Between 1 and 255 share streams are required",
+            ));
+        }
+        if shares.len() < self.0 as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Not enough share streams to reach the threshold",
+            ));
+        }
+
+        for (i, writer) in shares.iter_mut().enumerate() {
+            StreamHeader {
+                threshold: self.0,
+                x: i as u8 + 1,
+                poly: POLY,
+            }
+            .write_to(writer)?;
+        }
+
+        let mut chunk = vec![0u8; STREAM_CHUNK_LENGTH];
+        let mut bytes = Vec::with_capacity(STREAM_CHUNK_LENGTH);
+        let mut total = 0u64;
+        let result = loop {
+            let read = match fill(&mut secret, &mut chunk) {
+                Ok(0) => break Ok(total),
+                Ok(read) => read,
+                Err(e) => break Err(e),
+            };
+
+            let dealer = self.dealer_rng(&chunk[..read], rng);
+            let written = shares
+                .iter_mut()
+                .zip(dealer)
+                .try_for_each(|(writer, share)| {
+                    bytes.clear();
+                    bytes.extend(share.y.iter().map(|p| p.0));
+                    writer.write_all(&bytes)
+                });
+            if let Err(e) = written {
+                break Err(e);
+            }
+            total += read as u64;
+        };
+
+        #[cfg(feature = "zeroize_memory")]
+        {
+            use zeroize::Zeroize;
+            chunk.zeroize();
+            bytes.zeroize();
+        }
+
+        result?;
+        for writer in shares.iter_mut() {
+            writer.flush()?;
+        }
+        Ok(total)
+    }
+
+    /// Reads share streams written by `split_stream` in lock-step chunks, recovers the secret and
+    /// writes it to `secret`. Memory use is bounded by the chunk length times the number of shares.
+    /// Returns the number of secret bytes written.
+    ///
+    /// An `Err` is returned if the headers do not match this sharing, if there are not enough
+    /// distinct shares, or if the share streams do not all have the same length, in which case the
+    /// error message includes the offset at which the shortest stream ends.
+    ///
+    /// Example:
+    /// ```
+    /// # use ssskit::SecretSharing;
+    /// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    /// # const POLY: u16 = 0x11d_u16;
+    /// # let sss = SecretSharing::<POLY>(2);
+    /// # let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// # let secret = vec![7u8; 100_000];
+    /// # let mut shares = vec![Vec::new(); 3];
+    /// # sss.split_stream(secret.as_slice(), &mut shares, &mut rng).unwrap();
+    /// let mut readers = [shares[0].as_slice(), shares[2].as_slice()];
+    /// let mut recovered = Vec::new();
+    /// sss.combine_stream(&mut readers, &mut recovered).unwrap();
+    /// assert_eq!(recovered, secret);
+    /// ```
+    pub fn combine_stream<R: Read, W: Write>(
+        &self,
+        shares: &mut [R],
+        mut secret: W,
+    ) -> io::Result<u64> {
+        let mut xs = HashSet::new();
+        let mut x_samples = Vec::with_capacity(shares.len());
+        for (i, reader) in shares.iter_mut().enumerate() {
+            let header = StreamHeader::read_from(reader)?;
+            if header.poly != POLY {
+                return Err(invalid_data(&format!(
+                    "Share stream {} was split over polynomial {:#x}, not {:#x}",
+                    i, header.poly, POLY
+                )));
+            }
+            if header.threshold != self.0 {
+                return Err(invalid_data(&format!(
+                    "Share stream {} has threshold {}, not {}",
+                    i, header.threshold, self.0
+                )));
+            }
+            if !xs.insert(header.x) {
+                return Err(invalid_data(&format!(
+                    "Share stream {} duplicates the share with x = {}",
+                    i, header.x
+                )));
+            }
+            x_samples.push(GF256::<POLY>(header.x));
+        }
+
+        if x_samples.is_empty() || x_samples.len() < self.0 as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Not enough shares to recover original secret",
+            ));
+        }
+
+        let weights = math::lagrange_coefficients(&x_samples, GF256(0));
+        let mut chunks = vec![vec![0u8; STREAM_CHUNK_LENGTH]; shares.len()];
+        let mut bytes = Vec::with_capacity(STREAM_CHUNK_LENGTH);
+        let mut total = 0u64;
+        let result = loop {
+            let lengths = shares
+                .iter_mut()
+                .zip(chunks.iter_mut())
+                .map(|(reader, chunk)| fill(reader, chunk))
+                .collect::<io::Result<Vec<usize>>>();
+            let lengths = match lengths {
+                Ok(lengths) => lengths,
+                Err(e) => break Err(e),
+            };
+
+            let shortest = (0..lengths.len()).min_by_key(|&i| lengths[i]).unwrap();
+            let longest = (0..lengths.len()).max_by_key(|&i| lengths[i]).unwrap();
+            if lengths[shortest] != lengths[longest] {
+                break Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    format!(
+                        "All shares must have the same length: share stream {} ends at offset {} \
+                         while share stream {} continues",
+                        shortest,
+                        total + lengths[shortest] as u64,
+                        longest
+                    ),
+                ));
+            }
+
+            let read = lengths[0];
+            if read == 0 {
+                break Ok(total);
+            }
+
+            bytes.clear();
+            bytes.extend((0..read).map(|b| {
+                weights
+                    .iter()
+                    .zip(chunks.iter())
+                    .map(|(w, chunk)| w.clone() * GF256(chunk[b]))
+                    .sum::<GF256<POLY>>()
+                    .0
+            }));
+            if let Err(e) = secret.write_all(&bytes) {
+                break Err(e);
+            }
+            total += read as u64;
+        };
+
+        #[cfg(feature = "zeroize_memory")]
+        {
+            use zeroize::Zeroize;
+            chunks.zeroize();
+            bytes.zeroize();
+        }
+
+        let total = result?;
+        secret.flush()?;
+        Ok(total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{StreamHeader, STREAM_CHUNK_LENGTH};
+    use crate::{SecretSharing, Share};
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    use rstest::rstest;
+
+    const POLY: u16 = 0x11d_u16;
+
+    #[rstest]
+    #[case(0)]
+    #[case(1)]
+    #[case(STREAM_CHUNK_LENGTH)]
+    #[case(2 * STREAM_CHUNK_LENGTH + 17)]
+    fn split_stream_works(#[case] length: usize) {
+        let sss = SecretSharing::<POLY>(3);
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        let secret: Vec<u8> = (0..length).map(|i| i as u8).collect();
+        let mut streams = vec![Vec::new(); 4];
+        let read = sss
+            .split_stream(secret.as_slice(), &mut streams, &mut rng)
+            .unwrap();
+        assert_eq!(read, length as u64);
+
+        for (i, stream) in streams.iter().enumerate() {
+            assert_eq!(stream.len(), StreamHeader::LENGTH + length);
+            let header = StreamHeader::read_from(&mut stream.as_slice()).unwrap();
+            assert_eq!(
+                header,
+                StreamHeader {
+                    threshold: 3,
+                    x: i as u8 + 1,
+                    poly: POLY
+                }
+            );
+        }
+
+        if length > 1 {
+            #[allow(unused_variables)]
+            let mut shares: Vec<Option<Share<POLY>>> = streams
+                .iter()
+                .enumerate()
+                .map(|(i, s)| {
+                    let y = &s[StreamHeader::LENGTH..];
+                    #[cfg(feature = "share_x")]
+                    let bytes = [&[i as u8 + 1], y].concat();
+                    #[cfg(not(feature = "share_x"))]
+                    let bytes = y.to_vec();
+                    Some(Share::<POLY>::try_from(bytes.as_slice()).unwrap())
+                })
+                .collect();
+            shares[0] = None;
+            assert_eq!(sss.recover(&shares).unwrap(), secret);
+        }
+    }
+
+    #[test]
+    fn split_stream_invalid_writers_err() {
+        let sss = SecretSharing::<POLY>(3);
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        let mut streams = vec![Vec::new(); 2];
+        assert!(sss
+            .split_stream([1u8, 2].as_slice(), &mut streams, &mut rng)
+            .is_err());
+        let mut streams: Vec<Vec<u8>> = Vec::new();
+        assert!(sss
+            .split_stream([1u8, 2].as_slice(), &mut streams, &mut rng)
+            .is_err());
+    }
+
+    fn split(k: u8, n: usize, length: usize) -> (Vec<u8>, Vec<Vec<u8>>) {
+        let sss = SecretSharing::<POLY>(k);
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        let secret: Vec<u8> = (0..length).map(|i| (i * 7) as u8).collect();
+        let mut streams = vec![Vec::new(); n];
+        sss.split_stream(secret.as_slice(), &mut streams, &mut rng)
+            .unwrap();
+        (secret, streams)
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(1)]
+    #[case(STREAM_CHUNK_LENGTH)]
+    #[case(2 * STREAM_CHUNK_LENGTH + 17)]
+    fn combine_stream_works(#[case] length: usize) {
+        let sss = SecretSharing::<POLY>(3);
+        let (secret, streams) = split(3, 5, length);
+        let mut readers = [
+            streams[4].as_slice(),
+            streams[1].as_slice(),
+            streams[2].as_slice(),
+        ];
+        let mut recovered = Vec::new();
+        let written = sss.combine_stream(&mut readers, &mut recovered).unwrap();
+        assert_eq!(written, length as u64);
+        assert_eq!(recovered, secret);
+    }
+
+    #[test]
+    fn combine_stream_truncated_err() {
+        let sss = SecretSharing::<POLY>(2);
+        let (_, streams) = split(2, 3, STREAM_CHUNK_LENGTH + 100);
+        let truncated = &streams[1][..streams[1].len() - 10];
+        let mut readers = [streams[0].as_slice(), truncated];
+        let mut recovered = Vec::new();
+        let err = sss
+            .combine_stream(&mut readers, &mut recovered)
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+        let offset = format!("offset {}", STREAM_CHUNK_LENGTH + 90);
+        assert!(err.to_string().contains(&offset));
+        assert!(err.to_string().contains("share stream 1 ends"));
+        assert_eq!(recovered.len(), STREAM_CHUNK_LENGTH);
+    }
+
+    #[test]
+    fn combine_stream_invalid_headers_err() {
+        let (_, streams) = split(2, 3, 10);
+        let mut recovered = Vec::new();
+
+        let mut readers = [streams[0].as_slice(), streams[0].as_slice()];
+        assert!(SecretSharing::<POLY>(2)
+            .combine_stream(&mut readers, &mut recovered)
+            .is_err());
+
+        let mut readers = [streams[0].as_slice(), streams[1].as_slice()];
+        assert!(SecretSharing::<POLY>(3)
+            .combine_stream(&mut readers, &mut recovered)
+            .is_err());
+        assert!(SecretSharing::<0x11b>(2)
+            .combine_stream(&mut readers, &mut recovered)
+            .is_err());
+
+        let mut readers = [streams[0].as_slice()];
+        assert!(SecretSharing::<POLY>(2)
+            .combine_stream(&mut readers, &mut recovered)
+            .is_err());
+        assert!(recovered.is_empty());
+    }
+
+    #[test]
+    fn header_roundtrip() {
+        let header = StreamHeader {
+            threshold: 5,
+            x: 200,
+            poly: 0x11b,
+        };
+        let mut bytes = Vec::new();
+        header.write_to(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), StreamHeader::LENGTH);
+        assert_eq!(
+            StreamHeader::read_from(&mut bytes.as_slice()).unwrap(),
+            header
+        );
+
+        bytes[0] = b'X';
+        assert!(StreamHeader::read_from(&mut bytes.as_slice()).is_err());
+        assert!(StreamHeader::read_from(&mut &bytes[..3]).is_err());
+    }
+}
@@ -0,0 +1,283 @@
+// Weighted threshold sharing: a participant of weight `w` holds a bundle of `w` shares with
+// consecutive `x` coordinates, and the secret is recovered once the presented bundles reach a total
+// weight of `k`.
+
+use alloc::vec::Vec;
+
+use super::share::Share;
+use super::SecretSharing;
+
+/// A bundle of shares held by a single participant of a weighted sharing, whose weight is the
+/// number of shares. The shares have consecutive `x` coordinates starting at `start`.
+///
+/// Can be serialized to and from a byte array as `[start, weight, share bytes...]`, where all the
+/// shares have the same serialized length.
+#[derive(Clone)]
+pub struct ShareBundle<const POLY: u16> {
+    /// The x coordinate of the first share of the bundle.
+    pub start: u8,
+    /// The shares of the bundle.
+    pub shares: Vec<Share<POLY>>,
+}
+
+impl<const POLY: u16> ShareBundle<POLY> {
+    /// Returns the weight of the bundle, i.e. its number of shares.
+    pub fn weight(&self) -> usize {
+        self.shares.len()
+    }
+}
+
+/// Checks that the shares of a bundle starting at `start` have consecutive `x` coordinates from it.
+#[cfg(feature = "share_x")]
+fn check_x<const POLY: u16>(start: u8, shares: &[Share<POLY>]) -> Result<(), &'static str> {
+    if shares
+        .iter()
+        .enumerate()
+        .any(|(i, share)| share.x.0 as usize != start as usize + i)
+    {
+        return Err("Bundle shares must have consecutive x coordinates from its start");
+    }
+    Ok(())
+}
+
+impl<const POLY: u16> From<&ShareBundle<POLY>> for Vec<u8> {
+    fn from(b: &ShareBundle<POLY>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.push(b.start);
+        bytes.push(b.shares.len() as u8);
+        for share in &b.shares {
+            bytes.extend(Vec::from(share));
+        }
+        bytes
+    }
+}
+
+impl<const POLY: u16> core::convert::TryFrom<&[u8]> for ShareBundle<POLY> {
+    type Error = &'static str;
+
+    fn try_from(b: &[u8]) -> Result<ShareBundle<POLY>, Self::Error> {
+        if b.len() < 2 || b[0] == 0 || b[1] == 0 {
+            return Err("A ShareBundle must start with a non-zero x and weight");
+        }
+        let (start, weight) = (b[0], b[1] as usize);
+        if start as usize + weight - 1 > u8::MAX as usize {
+            return Err("A ShareBundle x coordinates must not exceed 255");
+        }
+        let bytes = &b[2..];
+        if bytes.is_empty() || !bytes.len().is_multiple_of(weight) {
+            return Err("A ShareBundle must hold shares of the same length");
+        }
+
+        let shares = bytes
+            .chunks_exact(bytes.len() / weight)
+            .map(Share::try_from)
+            .collect::<Result<Vec<Share<POLY>>, _>>()?;
+        #[cfg(feature = "share_x")]
+        check_x(start, &shares)?;
+        Ok(ShareBundle { start, shares })
+    }
+}
+
+impl<const POLY: u16> SecretSharing<POLY> {
+    /// Given a `secret` byte slice and the weight of each participant, returns one bundle of
+    /// shares per participant, where the threshold is the total weight required to recover
+    /// the secret. Weights must be non-zero and sum up to at least the threshold and at most 255.
+    /// A random number generator has to be provided.
+    ///
+    /// Example:
+    /// ```
+    /// # use ssskit::{ SecretSharing, ShareBundle };
+    /// # use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    /// # const POLY: u16 = 0x11d_u16;
+    /// // Directors weigh 3, managers 2 and engineers 1, and a total weight of 6 recovers the secret
+    /// let sss = SecretSharing::<POLY>(6);
+    /// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// let bundles = sss
+    ///     .dealer_weighted_rng(&[1, 2, 3], &[3, 3, 2, 2, 1, 1, 1], &mut rng)
+    ///     .unwrap();
+    /// // A director and a manager are not enough
+    /// assert!(sss.recover_weighted(&bundles[1..3]).is_err());
+    /// // A director, a manager and an engineer are
+    /// let bundles = [&bundles[0], &bundles[2], &bundles[5]];
+    /// assert_eq!(sss.recover_weighted(bundles).unwrap(), vec![1, 2, 3]);
+    /// ```
+    pub fn dealer_weighted_rng<R: rand::Rng>(
+        &self,
+        secret: &[u8],
+        weights: &[u8],
+        rng: &mut R,
+    ) -> Result<Vec<ShareBundle<POLY>>, &'static str> {
+        if weights.contains(&0) {
+            return Err("Participant weights must be non-zero");
+        }
+        let total = weights.iter().map(|w| *w as usize).sum::<usize>();
+        if total > u8::MAX as usize {
+            return Err("Participant weights must sum up to at most 255");
+        }
+        if total < self.0 as usize {
+            return Err("Participant weights must sum up to at least the threshold");
+        }
+
+        let mut dealer = self.dealer_rng(secret, rng);
+        let mut start = 1;
+        Ok(weights
+            .iter()
+            .map(|w| {
+                let bundle = ShareBundle {
+                    start,
+                    shares: dealer.by_ref().take(*w as usize).collect(),
+                };
+                start = start.wrapping_add(*w);
+                bundle
+            })
+            .collect())
+    }
+
+    /// Same as `dealer_weighted_rng`, using `rand::thread_rng`.
+    #[cfg(feature = "std")]
+    pub fn dealer_weighted(
+        &self,
+        secret: &[u8],
+        weights: &[u8],
+    ) -> Result<Vec<ShareBundle<POLY>>, &'static str> {
+        let mut rng = rand::thread_rng();
+        self.dealer_weighted_rng(secret, weights, &mut rng)
+    }
+
+    /// Given an iterable collection of share bundles, recovers the original secret.
+    /// If the bundles overlap or their total weight is less than the threshold an `Err` is
+    /// returned, otherwise an `Ok` containing the secret. With `share_x`, the shares of each bundle
+    /// must have consecutive `x` coordinates from its start.
+    pub fn recover_weighted<'a, T>(&self, bundles: T) -> Result<Vec<u8>, &'static str>
+    where
+        T: IntoIterator<Item = &'a ShareBundle<POLY>>,
+        T::IntoIter: Iterator<Item = &'a ShareBundle<POLY>>,
+    {
+        // Without `share_x` the `x` coordinate of a share is its position, so every share is
+        // placed at position `x - 1`.
+        let mut shares: Vec<Option<Share<POLY>>> = alloc::vec![None; u8::MAX as usize];
+        let mut weight = 0;
+        for bundle in bundles {
+            let start = bundle.start as usize;
+            if start == 0 || start + bundle.weight() - 1 > u8::MAX as usize {
+                return Err("Bundle x coordinates must be in [1, 255]");
+            }
+            #[cfg(feature = "share_x")]
+            check_x(bundle.start, &bundle.shares)?;
+            for (i, share) in bundle.shares.iter().enumerate() {
+                if shares[start + i - 1].replace(share.clone()).is_some() {
+                    return Err("Bundles must not overlap");
+                }
+            }
+            weight += bundle.weight();
+        }
+
+        if weight < self.0 as usize {
+            return Err("Not enough total weight to recover original secret");
+        }
+        self.recover(&shares)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ShareBundle;
+    use crate::SecretSharing;
+    use alloc::{vec, vec::Vec};
+    use core::convert::TryFrom;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    use rstest::rstest;
+
+    const POLY: u16 = 0x11d_u16;
+
+    fn make_bundles(k: u8, weights: &[u8]) -> Vec<ShareBundle<POLY>> {
+        let mut rng = ChaCha8Rng::from_seed([0x37; 32]);
+        SecretSharing::<POLY>(k)
+            .dealer_weighted_rng(&[4, 5, 6, 7], weights, &mut rng)
+            .unwrap()
+    }
+
+    #[rstest]
+    #[case(6, vec![3, 2, 1], vec![0, 1, 2], true)]
+    #[case(6, vec![3, 3, 2, 2, 1, 1, 1], vec![2, 3, 4, 5], true)]
+    #[case(6, vec![3, 3, 2, 2, 1, 1, 1], vec![2, 4, 5, 6], false)]
+    #[case(6, vec![3, 3, 2, 2, 1, 1, 1], vec![0, 1], true)]
+    #[case(1, vec![1], vec![0], true)]
+    #[case(255, vec![200, 55], vec![0, 1], true)]
+    #[case(255, vec![200, 55], vec![0], false)]
+    fn weighted_recovery(
+        #[case] k: u8,
+        #[case] weights: Vec<u8>,
+        #[case] present: Vec<usize>,
+        #[case] recovers: bool,
+    ) {
+        let sss = SecretSharing::<POLY>(k);
+        let bundles = make_bundles(k, &weights);
+        assert!(bundles
+            .iter()
+            .zip(weights.iter())
+            .all(|(b, w)| b.weight() == *w as usize));
+
+        let result = sss.recover_weighted(present.iter().map(|&i| &bundles[i]));
+        if recovers {
+            assert_eq!(result.unwrap(), vec![4, 5, 6, 7]);
+        } else {
+            assert!(result.is_err());
+        }
+    }
+
+    #[test]
+    fn serialization_roundtrip() {
+        let sss = SecretSharing::<POLY>(4);
+        let bundles: Vec<ShareBundle<POLY>> = make_bundles(4, &[2, 2, 2])
+            .iter()
+            .map(|b| ShareBundle::try_from(Vec::from(b).as_slice()).unwrap())
+            .collect();
+        assert_eq!(bundles[2].start, 5);
+        assert_eq!(
+            sss.recover_weighted(&bundles[1..]).unwrap(),
+            vec![4, 5, 6, 7]
+        );
+
+        assert!(ShareBundle::<POLY>::try_from(&[1u8][..]).is_err());
+        assert!(ShareBundle::<POLY>::try_from(&[0u8, 1, 1, 2][..]).is_err());
+        assert!(ShareBundle::<POLY>::try_from(&[1u8, 2, 1, 2, 3][..]).is_err());
+        assert!(ShareBundle::<POLY>::try_from(&[255u8, 2, 1, 2, 3, 4][..]).is_err());
+    }
+
+    #[test]
+    fn invalid_weights_err() {
+        let sss = SecretSharing::<POLY>(2);
+        let mut rng = ChaCha8Rng::from_seed([0x37; 32]);
+        assert!(sss.dealer_weighted_rng(&[1], &[1, 0], &mut rng).is_err());
+        assert!(sss.dealer_weighted_rng(&[1], &[200, 56], &mut rng).is_err());
+        // The bundles could never recover the secret
+        let sss = SecretSharing::<POLY>(5);
+        assert!(sss.dealer_weighted_rng(&[1], &[2, 2], &mut rng).is_err());
+        assert!(sss.dealer_weighted_rng(&[1], &[2, 3], &mut rng).is_ok());
+    }
+
+    #[test]
+    fn overlapping_bundles_err() {
+        let sss = SecretSharing::<POLY>(3);
+        let mut bundles = make_bundles(3, &[2, 2]);
+        bundles[1].start = 2;
+        assert!(sss.recover_weighted(&bundles).is_err());
+        assert!(sss.recover_weighted([&bundles[0], &bundles[0]]).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "share_x")]
+    fn mismatched_start_err() {
+        let sss = SecretSharing::<POLY>(3);
+        let mut bundles = make_bundles(3, &[2, 2]);
+        // The shares of the second bundle are at x = 3 and 4
+        bundles[1].start = 5;
+        assert!(sss.recover_weighted(&bundles).is_err());
+        let mut bytes = Vec::from(&bundles[1]);
+        bytes[0] = 4;
+        assert!(ShareBundle::<POLY>::try_from(bytes.as_slice()).is_err());
+        bytes[0] = 3;
+        assert!(ShareBundle::<POLY>::try_from(bytes.as_slice()).is_ok());
+    }
+}